@@ -0,0 +1,35 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::{fs, io};
+
+/// Hash the file, or all the files in the directory, in a stable order.
+fn hash_path(path: &Path, hasher: &mut DefaultHasher) -> io::Result<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            hash_path(&entry, hasher)?;
+        }
+    } else if path.is_file() {
+        path.hash(hasher);
+        fs::read(path)?.hash(hasher);
+    }
+    Ok(())
+}
+
+fn main() {
+    // The posts are embedded with `include_dir!`, which does not tell Cargo about the files that it
+    // reads. Without this, new and edited posts are not picked up until something else changes.
+    println!("cargo:rerun-if-changed=posts");
+
+    // The build cache needs to know when the code that renders the pages changes. This hashes the
+    // sources rather than the binary since the binary also contains the posts.
+    let mut hasher = DefaultHasher::new();
+    for path in ["src", "Cargo.toml", "Cargo.lock"] {
+        println!("cargo:rerun-if-changed={path}");
+        hash_path(Path::new(path), &mut hasher).expect("failed to hash sources");
+    }
+    println!("cargo:rustc-env=BLOG_CODE_VERSION={:016x}", hasher.finish());
+}
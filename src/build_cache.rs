@@ -0,0 +1,131 @@
+//! A cache of the rendered pages. Trunk recreates the output directory on every build, so pages
+//! whose inputs have not changed since the last build are copied from the cache instead of being
+//! rendered again.
+
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the file in the cache directory that lists the cached pages.
+static MANIFEST: &str = "manifest.json";
+
+/// Directory inside the cache directory that the cached files are stored in.
+static FILES: &str = "files";
+
+/// A cached page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    /// Hash of the inputs that the page was rendered from.
+    hash: u64,
+    /// The files that were written when rendering the page, relative to the output directory.
+    files: Vec<String>,
+}
+
+/// The cached pages of the previous build, and the pages of the current build that will be cached
+/// for the next one.
+pub struct BuildCache {
+    dir: PathBuf,
+    /// Hash of the inputs that every page depends on.
+    base: u64,
+    /// The pages that were cached by the previous build, keyed by the path of the page.
+    previous: HashMap<String, Entry>,
+    /// The pages that are part of the current build.
+    current: Mutex<HashMap<String, Entry>>,
+}
+
+impl BuildCache {
+    /// Load the cache from `dir`. If `force` is set, the previous build is ignored so that every
+    /// page is rendered again.
+    ///
+    /// Every page depends on the code that renders it, the site configuration and whether drafts
+    /// are included, so changing any of them invalidates the whole cache. The posts are not part
+    /// of the code version since they are passed to [`BuildCache::page_hash`] for the pages that
+    /// use them.
+    pub fn load(dir: &Path, force: bool) -> Self {
+        let mut hasher = DefaultHasher::new();
+        env!("BLOG_CODE_VERSION").hash(&mut hasher);
+        serde_json::to_string(crate::config::site())
+            .expect("failed to serialize site config")
+            .hash(&mut hasher);
+        crate::pages::post::preview_enabled().hash(&mut hasher);
+
+        // A missing or invalid manifest just means that everything is rendered again.
+        let previous = match fs::read_to_string(dir.join(MANIFEST)) {
+            Ok(json) if !force => serde_json::from_str(&json).unwrap_or_default(),
+            _ => HashMap::new(),
+        };
+        Self {
+            dir: dir.to_path_buf(),
+            base: hasher.finish(),
+            previous,
+            current: Mutex::default(),
+        }
+    }
+
+    /// Hash of the inputs of the page at `path`. `inputs` is everything that the page is rendered
+    /// from other than the code and the site configuration, such as the posts that it shows.
+    pub fn page_hash(&self, path: &str, inputs: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.base.hash(&mut hasher);
+        path.hash(&mut hasher);
+        inputs.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Copy the files of the page from the cache into `out_dir` if the page was rendered from the
+    /// same inputs. Returns whether the page was restored.
+    pub fn restore(&self, path: &str, hash: u64, out_dir: &Path) -> bool {
+        let Some(entry) = self.previous.get(path).filter(|entry| entry.hash == hash) else {
+            return false;
+        };
+        for file in &entry.files {
+            let to = out_dir.join(file);
+            let copied = to
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::copy(self.dir.join(FILES).join(file), &to));
+            if copied.is_err() {
+                return false;
+            }
+        }
+        self.current
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), entry.clone());
+        true
+    }
+
+    /// Store the files that were written when rendering the page, including its server components.
+    pub fn store(&self, path: &str, hash: u64, files: &[(String, String)]) -> io::Result<()> {
+        for (file, contents) in files {
+            self.store_file(file, contents)?;
+        }
+        let entry = Entry {
+            hash,
+            files: files.iter().map(|(file, _)| file.clone()).collect(),
+        };
+        self.current.lock().unwrap().insert(path.to_string(), entry);
+        Ok(())
+    }
+
+    fn store_file(&self, file: &str, contents: &str) -> io::Result<()> {
+        let path = self.dir.join(FILES).join(file);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, contents)
+    }
+
+    /// Write the list of cached pages. Pages that are no longer part of the site are dropped.
+    pub fn save(&self) -> io::Result<()> {
+        let current = self.current.lock().unwrap();
+        let json = serde_json::to_string(&*current).map_err(io::Error::other)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(MANIFEST), json)
+    }
+}
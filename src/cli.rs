@@ -0,0 +1,144 @@
+//! Command line interface of the SSR binary.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand};
+use walkdir::WalkDir;
+
+use crate::pages::post::{posts_by_date, PostDate, POST_INDEX};
+
+#[derive(Debug, Parser)]
+#[command(about = "Generate the site")]
+pub struct Cli {
+    /// Defaults to `build` so that the Trunk hook can run the binary without any arguments.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Render the site into the output directory.
+    Build(BuildArgs),
+    /// Build the site and serve it, rebuilding when the sources of the site change.
+    Serve(ServeArgs),
+    /// Create a new post for today in `posts/<year>/<slug>.mdx`.
+    New {
+        /// The id of the post, used in its URL, e.g. `my-new-post`.
+        slug: String,
+    },
+    /// Check the posts for errors without writing any output.
+    Check,
+    /// List all the posts, including unpublished ones.
+    List,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct BuildArgs {
+    /// The directory that the site is written to. Overrides `build.out_dir` in `site.toml`.
+    #[arg(long)]
+    pub out_dir: Option<PathBuf>,
+    /// The URL where the site is deployed. Overrides `base_url` in `site.toml`.
+    #[arg(long)]
+    pub base_url: Option<String>,
+    /// Include drafts and scheduled posts.
+    #[arg(long)]
+    pub drafts: bool,
+    /// Render every page, even the ones that have not changed since the last build.
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// The directory that is served. This is where Trunk puts the site after running the build.
+    #[arg(long, default_value = "dist")]
+    pub dir: PathBuf,
+    #[arg(long, default_value_t = 8080)]
+    pub port: u16,
+}
+
+fn posts_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("posts")
+}
+
+/// Turn the slug into a title for the new post, e.g. `my-new-post` becomes "My new post".
+fn title_from_slug(slug: &str) -> String {
+    let title = slug.replace('-', " ");
+    let mut chars = title.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => title,
+    }
+}
+
+/// Create a new draft post with the given slug, dated today.
+pub fn new_post(slug: &str) -> Result<(), String> {
+    if !crate::posts::is_slug(slug) {
+        return Err(format!(
+            "invalid slug `{slug}`, it should only contain lowercase letters, digits and `-`"
+        ));
+    }
+
+    // Post ids need to be unique across all the years.
+    let file_name = format!("{slug}.mdx");
+    for entry in WalkDir::new(posts_dir()) {
+        let entry = entry.map_err(|err| err.to_string())?;
+        if entry.file_name().to_str() == Some(file_name.as_str()) {
+            return Err(format!(
+                "a post with the id `{slug}` already exists at `{}`",
+                entry.path().display()
+            ));
+        }
+    }
+
+    let date = PostDate::today();
+    let title = title_from_slug(slug);
+    let dir = posts_dir().join(date.year.to_string());
+    let path = dir.join(file_name);
+    let contents = format!(
+        "---\ntitle: {title}\ndate: {date}\ndesc: \"\"\ntags: []\ndraft: true\n---\n\n# {title}\n"
+    );
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    fs::write(&path, contents).map_err(|err| err.to_string())?;
+    eprintln!("Created `{}`", path.display());
+    Ok(())
+}
+
+/// Check all the posts for errors.
+pub fn check() -> Result<(), String> {
+    let errors = crate::posts::validate_posts();
+    for error in &errors {
+        eprintln!("error: {error}");
+    }
+    if !errors.is_empty() {
+        return Err(format!("found {} error(s) in posts", errors.len()));
+    }
+    eprintln!("No errors found in posts");
+    Ok(())
+}
+
+/// Print all the posts with their dates and tags, most recent first.
+pub fn list() {
+    for summary in posts_by_date() {
+        let post = &POST_INDEX[&summary.id];
+        let status = if post.draft {
+            " (draft)".to_string()
+        } else if let Some(date) = post.publish_after.filter(|_| !post.is_published()) {
+            format!(" (scheduled for {date})")
+        } else {
+            String::new()
+        };
+        let tags = post
+            .tags
+            .iter()
+            .map(|tag| format!("#{tag}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("{}  {}{status}", post.date, post.filename);
+        println!("            {}", post.title);
+        if !tags.is_empty() {
+            println!("            {tags}");
+        }
+    }
+}
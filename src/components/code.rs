@@ -0,0 +1,44 @@
+use mdsycx::FromMd;
+use sycamore::prelude::*;
+
+#[derive(Props, FromMd)]
+pub struct CodeProps {
+    pub children: Children,
+    pub class: String,
+}
+
+/// Display code. Fenced code blocks, which have a `language-*` class, are highlighted at
+/// build-time.
+#[component]
+pub fn Code(props: CodeProps) -> View {
+    let Some(lang) = props
+        .class
+        .strip_prefix("language-")
+        .map(ToString::to_string)
+    else {
+        return view! {
+            code(class=props.class) { (props.children) }
+        };
+    };
+
+    let html = highlighted(props.children, &lang);
+    let class = props.class.clone();
+
+    view! {
+        sycamore::web::NoHydrate {
+            code(class=class, dangerously_set_inner_html=html)
+        }
+    }
+}
+
+#[cfg_ssr]
+fn highlighted(children: Children, lang: &str) -> String {
+    use crate::server_component::{render_text, server_fragment};
+
+    server_fragment(crate::highlight::highlight(&render_text(children), lang))
+}
+
+#[cfg_not_ssr]
+fn highlighted(_children: Children, _lang: &str) -> String {
+    crate::server_component::next_fragment()
+}
@@ -1,29 +1,79 @@
 use mdsycx::FromMd;
 use sycamore::prelude::*;
 
+/// Context value for whether math should be rendered in the current post. This is set from the
+/// `render_math` front matter field.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderMath(pub bool);
+
 #[derive(Props, FromMd)]
 pub struct MathDisplayProps {
     pub children: Children,
     pub class: String,
 }
 
-/// Display math content. If `class` is "math math-display", the content is wrapped in `$$`.
+/// Display math content. If `class` is "math math-display" or "math math-inline", the LaTeX
+/// content is converted to MathML at build-time.
 ///
-/// This allows MathJax to detect and render the content as math.
+/// If math rendering is disabled for the post, the content is shown as is, wrapped in the original
+/// delimiters.
 #[component]
 pub fn MathDisplay(props: MathDisplayProps) -> View {
-    let children = props.children.call();
     let class = props.class;
 
-    let delimiters = match class.as_str() {
-        "math math-display" => ("$$", "$$"),
-        "math math-inline" => ("\\(", "\\)"),
-        _ => ("", ""),
+    let (display, delimiters) = match class.as_str() {
+        "math math-display" => (true, ("$$", "$$")),
+        "math math-inline" => (false, ("\\(", "\\)")),
+        _ => {
+            let children = props.children.call();
+            return view! {
+                span(class=class) { (children) }
+            };
+        }
     };
 
+    let render_math = try_use_context::<RenderMath>().is_some_and(|render_math| render_math.0);
+    if !render_math {
+        let children = props.children.call();
+        return view! {
+            span(class=class) {
+                (delimiters.0) (children) (delimiters.1)
+            }
+        };
+    }
+
+    let html = mathml(props.children, display);
+
     view! {
-        span(class=class) {
-            (delimiters.0) (children) (delimiters.1)
+        sycamore::web::NoHydrate {
+            span(class=class, dangerously_set_inner_html=html)
         }
     }
 }
+
+#[cfg_ssr]
+fn mathml(children: Children, display: bool) -> String {
+    use crate::server_component::{render_text, server_fragment};
+    use latex2mathml::{latex_to_mathml, DisplayStyle};
+
+    let latex = render_text(children);
+    let style = if display {
+        DisplayStyle::Block
+    } else {
+        DisplayStyle::Inline
+    };
+    let html = latex_to_mathml(&latex, style).unwrap_or_else(|err| {
+        eprintln!("warning: could not render math `{latex}`: {err}");
+        // Fall back to showing the source.
+        latex
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    });
+    server_fragment(html)
+}
+
+#[cfg_not_ssr]
+fn mathml(_children: Children, _display: bool) -> String {
+    crate::server_component::next_fragment()
+}
@@ -1,29 +1,85 @@
+pub mod code;
 pub mod math;
 pub mod slides;
+pub mod toc;
 
 use mdsycx::FromMd;
 use sycamore::prelude::*;
 
 use crate::pages::post::PostDate;
 
+/// Context value for the length of the current post, which is shown next to the date by
+/// [`ShowDate`].
+#[derive(Debug, Clone)]
+pub struct PostLength(pub String);
+
 #[derive(Props, FromMd)]
 pub struct ShowDateProps {
     pub date: PostDate,
 }
 
-/// Display date string
+/// Display date string, along with the length of the post if there is a [`PostLength`] context.
 #[component]
 pub fn ShowDate(ShowDateProps { date }: ShowDateProps) -> View {
+    let length = try_use_context::<PostLength>().map(|length| length.0);
+    date_line(date, length)
+}
+
+/// The date along with an optional description of the length of the post.
+pub fn date_line(date: PostDate, length: Option<String>) -> View {
     let day = date.day.to_string();
 
     static MONTHS: &[&str] = &[
         "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
     ];
-    let month = MONTHS.get(date.month as usize - 1).expect("invalid month");
-
     let year = date.year.to_string();
 
+    // Fall back to the numeric format if the month is invalid.
+    let text = match date
+        .month
+        .checked_sub(1)
+        .and_then(|i| MONTHS.get(i as usize))
+    {
+        Some(month) => format!("{month} {day}, {year}"),
+        None => date.to_string(),
+    };
+    let text = match length {
+        Some(length) => format!("{text} · {length}"),
+        None => text,
+    };
+
     view! {
-        p(class="text-sm text-gray-400 !mb-0 font-mono") { (format!("{month} {day}, {year}")) }
+        p(class="text-sm text-gray-400 !mb-0 font-mono") { (text) }
     }
 }
+
+/// Marks a post that is not published on the live site. These are only rendered in preview mode.
+#[component]
+pub fn DraftBadge() -> View {
+    view! {
+        span(class="inline-block px-2 rounded bg-red-900 text-red-100 text-xs font-mono font-bold") {
+            "DRAFT"
+        }
+    }
+}
+
+/// Add an event listener on the window that is removed when the current scope is disposed.
+#[cfg_not_ssr]
+pub fn add_window_listener<E: wasm_bindgen::JsCast>(
+    event: &'static str,
+    mut f: impl FnMut(E) + 'static,
+) {
+    use wasm_bindgen::prelude::*;
+
+    let closure = Closure::<dyn FnMut(web_sys::Event)>::new(move |ev: web_sys::Event| {
+        f(ev.unchecked_into());
+    });
+    window()
+        .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+        .unwrap();
+    on_cleanup(move || {
+        window()
+            .remove_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+            .unwrap();
+    });
+}
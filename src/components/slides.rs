@@ -4,21 +4,143 @@ use std::str::FromStr;
 
 use mdsycx::FromMd;
 use serde::Deserialize;
+#[cfg_not_ssr]
+use serde::Serialize;
 use sycamore::prelude::*;
 use sycamore::web::Suspense;
 use wasm_bindgen::prelude::*;
 
+#[cfg_not_ssr]
+use super::add_window_listener;
+
 /// Context state used to manage slides.
 #[derive(Debug, Default, Clone, Copy)]
 struct SlideShowState {
     slides: Signal<Vec<SlideData>>,
     current_slide: Signal<usize>,
     current_segment: Signal<usize>,
+    /// Whether the last step went back one segment. In this case, the reversed video is played
+    /// instead of jumping to the previous segment.
+    reversed: Signal<bool>,
+    mode: Signal<SlideShowMode>,
+}
+
+/// How the slide show is displayed in the current window. This is set with the `view` query
+/// parameter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum SlideShowMode {
+    /// The normal view.
+    #[default]
+    Audience,
+    /// Shows the slides along with the speaker notes, a timer and a preview of the next segment.
+    Presenter,
+    /// Follows the other windows but always shows the next segment. This is used for the preview
+    /// in the presenter view.
+    Next,
+}
+
+impl FromStr for SlideShowMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "audience" => Ok(Self::Audience),
+            "presenter" => Ok(Self::Presenter),
+            "next" => Ok(Self::Next),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SlideShowMode {
+    /// Read the mode from the `view` query parameter, ignoring any other parameters.
+    #[cfg_not_ssr]
+    fn from_url() -> Self {
+        let search = window().location().search().unwrap_or_default();
+        web_sys::UrlSearchParams::new_with_str(&search)
+            .ok()
+            .and_then(|params| params.get("view"))
+            .and_then(|view| view.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Messages sent between the windows showing the same slide show to keep them in sync.
+#[cfg_not_ssr]
+#[derive(Debug, Serialize, Deserialize)]
+enum SlideMessage {
+    /// Go to the given slide and segment.
+    Go { slide: usize, segment: usize },
+    /// Ask the other windows to send their current position.
+    Sync,
+}
+
+impl SlideShowState {
+    /// The number of segments in the given slide.
+    fn segment_count(self, slide: usize) -> usize {
+        self.slides
+            .with(|slides| slides.get(slide).map_or(0, |slide| slide.segments.len()))
+    }
+
+    /// Go to the given slide and segment, clamping them to the valid range.
+    fn go_to(self, slide: usize, segment: usize) {
+        let slide = slide.min(self.slides.with(Vec::len).saturating_sub(1));
+        let segment = segment.min(self.segment_count(slide).saturating_sub(1));
+        self.reversed.set(false);
+        self.current_slide.set(slide);
+        self.current_segment.set(segment);
+    }
+
+    /// Advance to the next segment of the current slide.
+    fn next_segment(self) {
+        self.reversed.set(false);
+        self.current_segment.update(|segment| *segment += 1);
+    }
+
+    /// Step forward to the next segment, or to the next slide if this is the last segment.
+    #[cfg_not_ssr]
+    fn next(self) {
+        let slide = self.current_slide.get();
+        let segment = self.current_segment.get();
+        if segment + 1 < self.segment_count(slide) {
+            self.next_segment();
+        } else if slide + 1 < self.slides.with(Vec::len) {
+            self.go_to(slide + 1, 0);
+        }
+    }
+
+    /// Step back to the previous segment, or to the last segment of the previous slide if this is
+    /// the first segment.
+    #[cfg_not_ssr]
+    fn previous(self) {
+        let slide = self.current_slide.get();
+        let segment = self.current_segment.get();
+        if segment > 0 {
+            self.reversed.set(true);
+            self.current_segment.set(segment - 1);
+        } else if slide > 0 {
+            self.go_to(slide - 1, usize::MAX);
+        }
+    }
+
+    /// Go to the first segment of the first slide.
+    #[cfg_not_ssr]
+    fn first(self) {
+        self.go_to(0, 0);
+    }
+
+    /// Go to the last segment of the last slide.
+    #[cfg_not_ssr]
+    fn last(self) {
+        self.go_to(usize::MAX, usize::MAX);
+    }
 }
 
 #[derive(Debug, Clone)]
 struct SlideData {
     pub segments: Vec<SlideSegmentData>,
+    /// The speaker notes for each video segment from the manim-slides JSON.
+    pub video_notes: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,30 +173,44 @@ pub fn SlideShow(props: SlideShowProps) -> View {
         let state = SlideShowState::default();
 
         is_not_ssr! {
-            // Try to restore the slide number from the URL hash.
+            // Try to restore the slide and segment number from the URL hash, which is either of
+            // the form `#slide-N` or `#slide-N-M`.
             // However, we set the current slide in an `on_mount` to ensure that we properly
             // hydrate first.
             let hash = window().location().hash().unwrap();
-            let hash = hash.trim_start_matches("#slide-");
-            let slide = hash.parse::<usize>().unwrap_or(0);
+            let mut parts = hash.trim_start_matches("#slide-").splitn(2, '-');
+            let slide = parts.next().and_then(|n| n.parse::<usize>().ok()).unwrap_or(0);
+            let segment = parts.next().and_then(|m| m.parse::<usize>().ok()).unwrap_or(0);
+
+            let mode = SlideShowMode::from_url();
+            on_mount(move || {
+                state.mode.set(mode);
+                state.go_to(slide, segment);
+            });
 
-            on_mount(move || state.current_slide.set(slide));
+            // The preview only follows the other windows.
+            if mode != SlideShowMode::Next {
+                register_navigation(state);
+            }
+            on_mount(move || sync_windows(state, mode));
         }
 
-        // Create an effect that stores the slide number in the URL hash. We use this to restore the state when reloading the page.
+        // Create an effect that stores the slide and segment number in the URL hash. We use this
+        // to restore the state when reloading the page.
         //
         // We put this in an on_mount to ensure that the effect runs after sycamore-router is done
         // because otherwise our URL will be overwritten.
         on_mount(move || {
             create_effect(move || {
                 let current_slide = state.current_slide.get();
+                let current_segment = state.current_segment.get();
                 window()
                     .history()
                     .unwrap()
                     .replace_state_with_url(
                         &JsValue::null(),
                         "",
-                        Some(&format!("#slide-{current_slide}")),
+                        Some(&format!("#slide-{current_slide}-{current_segment}")),
                     )
                     .unwrap();
             });
@@ -82,18 +218,169 @@ pub fn SlideShow(props: SlideShowProps) -> View {
 
         provide_context(state);
 
+        // In the presenter view, the slides are shrunk to make room for the presenter panel. The
+        // transform makes the container the containing block for the fixed position slides.
+        let container_class = move || match state.mode.get() {
+            SlideShowMode::Presenter => "fixed top-0 left-0 h-full w-2/3 transform-gpu",
+            _ => "",
+        };
+        let controls_class = move || match state.mode.get() {
+            SlideShowMode::Next => "hidden",
+            _ => "fixed bottom-0 left-0 bg-slate-900 w-full p-2",
+        };
+
         view = view! {
-            div(class="slide") {
-                (props.children)
-            }
-            div(class="fixed bottom-0 left-0 bg-slate-900 w-full p-2") {
-                SlideControls()
+            div(class=container_class) {
+                div(class="slide") {
+                    (props.children)
+                }
+                div(class=controls_class) {
+                    SlideControls()
+                }
             }
+            (if state.mode.get() == SlideShowMode::Presenter {
+                view! { PresenterPanel() }
+            } else {
+                view! {}
+            })
         };
     });
     view
 }
 
+/// Keep the slide show in sync with the other windows showing it, e.g. the audience and presenter
+/// windows, using a `BroadcastChannel`.
+#[cfg_not_ssr]
+fn sync_windows(state: SlideShowState, mode: SlideShowMode) {
+    fn post(channel: &web_sys::BroadcastChannel, message: &SlideMessage) {
+        let message = serde_json::to_string(message).expect("failed to serialize message");
+        let _ = channel.post_message(&JsValue::from_str(&message));
+    }
+
+    let pathname = window().location().pathname().unwrap();
+    let Ok(channel) = web_sys::BroadcastChannel::new(&format!("slides:{pathname}")) else {
+        return;
+    };
+
+    let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new({
+        let channel = channel.clone();
+        move |ev: web_sys::MessageEvent| {
+            let Some(message) = ev
+                .data()
+                .as_string()
+                .and_then(|data| serde_json::from_str::<SlideMessage>(&data).ok())
+            else {
+                return;
+            };
+            let current = (state.current_slide.get(), state.current_segment.get());
+            match message {
+                SlideMessage::Go { slide, segment } if mode == SlideShowMode::Next => {
+                    state.go_to(slide, segment);
+                    state.next();
+                }
+                // Step back the same way so that the reversed video is played.
+                SlideMessage::Go { slide, segment } if (slide, segment + 1) == current => {
+                    state.previous();
+                }
+                // Only update if the position changed to avoid bouncing messages back and forth.
+                SlideMessage::Go { slide, segment } if (slide, segment) != current => {
+                    state.go_to(slide, segment);
+                }
+                SlideMessage::Go { .. } => {}
+                SlideMessage::Sync if mode != SlideShowMode::Next => post(
+                    &channel,
+                    &SlideMessage::Go {
+                        slide: current.0,
+                        segment: current.1,
+                    },
+                ),
+                SlideMessage::Sync => {}
+            }
+        }
+    });
+    channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+    if mode == SlideShowMode::Next {
+        post(&channel, &SlideMessage::Sync);
+    } else {
+        let channel = channel.clone();
+        create_effect(move || {
+            let slide = state.current_slide.get();
+            let segment = state.current_segment.get();
+            post(&channel, &SlideMessage::Go { slide, segment });
+        });
+    }
+
+    on_cleanup(move || {
+        channel.set_onmessage(None);
+        channel.close();
+        drop(on_message);
+    });
+}
+
+/// Whether the event target is a form field or editable element, in which case key presses are
+/// for typing and should not move the slide show.
+#[cfg_not_ssr]
+fn is_editable(target: Option<web_sys::EventTarget>) -> bool {
+    let Some(element) = target.and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+    else {
+        return false;
+    };
+    matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        || element.is_content_editable()
+}
+
+/// Register keyboard and touch navigation for the slide show.
+///
+/// The arrow keys, PageUp/PageDown and space step through the segments and then the slides, and
+/// Home/End jump to the start and end of the slide show. Swiping left and right does the same as
+/// the arrow keys.
+#[cfg_not_ssr]
+fn register_navigation(state: SlideShowState) {
+    add_window_listener("keydown", move |ev: web_sys::KeyboardEvent| {
+        if ev.default_prevented() || ev.ctrl_key() || ev.meta_key() || ev.alt_key() {
+            return;
+        }
+        if ev.is_composing() || is_editable(ev.target()) {
+            return;
+        }
+        match ev.key().as_str() {
+            "ArrowRight" | "ArrowDown" | "PageDown" | " " => state.next(),
+            "ArrowLeft" | "ArrowUp" | "PageUp" => state.previous(),
+            "Home" => state.first(),
+            "End" => state.last(),
+            _ => return,
+        }
+        ev.prevent_default();
+    });
+
+    // Only count horizontal swipes that travel far enough so that we do not interfere with
+    // scrolling the slide content.
+    const SWIPE_THRESHOLD: i32 = 50;
+    let touch_start = create_signal(None::<(i32, i32)>);
+    add_window_listener("touchstart", move |ev: web_sys::TouchEvent| {
+        let touch = ev.changed_touches().get(0);
+        touch_start.set(touch.map(|touch| (touch.client_x(), touch.client_y())));
+    });
+    add_window_listener("touchend", move |ev: web_sys::TouchEvent| {
+        let (Some((start_x, start_y)), Some(touch)) =
+            (touch_start.get(), ev.changed_touches().get(0))
+        else {
+            return;
+        };
+        touch_start.set(None);
+        let dx = touch.client_x() - start_x;
+        let dy = touch.client_y() - start_y;
+        if dx.abs() > SWIPE_THRESHOLD && dx.abs() > dy.abs() {
+            if dx < 0 {
+                state.next();
+            } else {
+                state.previous();
+            }
+        }
+    });
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub enum SlideKind {
     #[default]
@@ -127,11 +414,13 @@ pub fn Slide(props: SlideProps) -> View {
     let slide_number = state.slides.update(|slides| {
         slides.push(SlideData {
             segments: Vec::new(),
+            video_notes: Vec::new(),
         });
         slides.len() - 1
     });
 
     let show = move || state.current_slide.get() == slide_number;
+    let attempt = create_signal(0u32);
 
     let slide_content = match props.kind {
         SlideKind::Text => view! {
@@ -146,15 +435,20 @@ pub fn Slide(props: SlideProps) -> View {
                 }
                 div(class="sticky mt-5 top-5 h-fit mx-auto md:ml-0") {
                     (if show() {
+                        // Render the video again when retrying after an error.
+                        attempt.track();
                         let video_json = props.video_json.clone();
-                        let split = video_json.split('/').collect::<Vec<_>>();
-                        if split.len() < 2 {
+                        let Some(url_base) = manifest_base(&video_json).map(ToString::to_string) else {
                             return view! { "Error loading video" };
-                        }
-                        let url_base = split[..split.len() - 2].join("/");
+                        };
                         view! {
                             Suspense(fallback=|| "Loading...".into()) {
-                                ManimSlide(url_base=url_base, json_src=video_json)
+                                ManimSlide(
+                                    url_base=url_base,
+                                    json_src=video_json,
+                                    slide=slide_number,
+                                    on_retry=move || attempt.update(|attempt| *attempt += 1),
+                                )
                             }
                         }
                     } else {
@@ -219,6 +513,30 @@ pub fn SlideSegment(props: SlideSegmentProps) -> View {
     }
 }
 
+#[derive(Props, FromMd)]
+pub struct NotesProps {
+    pub children: Children,
+}
+
+/// Speaker notes that are only shown in the presenter view.
+#[component]
+pub fn Notes(props: NotesProps) -> View {
+    let state = use_context::<SlideShowState>();
+
+    let class = move || match state.mode.get() {
+        SlideShowMode::Presenter => {
+            "block border-l-4 border-amber-500 bg-amber-950 text-amber-100 p-2 my-4 rounded"
+        }
+        _ => "hidden",
+    };
+
+    view! {
+        aside(class=class) {
+            (props.children)
+        }
+    }
+}
+
 #[derive(Props, FromMd)]
 pub struct NextSegmentLinkProps {
     pub children: Children,
@@ -226,7 +544,7 @@ pub struct NextSegmentLinkProps {
 
 #[component]
 pub fn NextSegmentLink(props: NextSegmentLinkProps) -> View {
-    let mut state = use_context::<SlideShowState>();
+    let state = use_context::<SlideShowState>();
     let (current_slide, current_segment) = state
         .slides
         .with(|slides| (slides.len() - 1, slides.last().unwrap().segments.len() - 1));
@@ -237,7 +555,7 @@ pub fn NextSegmentLink(props: NextSegmentLinkProps) -> View {
 
     let on_click = move |_| {
         if active() {
-            state.current_segment += 1;
+            state.next_segment();
         }
     };
 
@@ -262,6 +580,7 @@ pub fn Video<F: Fn() -> bool + 'static>(
     video: String,
     show: F,
     r#loop: bool,
+    playback_rate: f64,
     #[prop(attributes(html, video))] attributes: Attributes,
 ) -> View {
     let state = use_context::<SlideShowState>();
@@ -279,6 +598,12 @@ pub fn Video<F: Fn() -> bool + 'static>(
     };
 
     on_mount(move || {
+        let video = video_ref
+            .get()
+            .unchecked_into::<web_sys::HtmlVideoElement>();
+        video.set_default_playback_rate(playback_rate);
+        video.set_playback_rate(playback_rate);
+
         create_effect(move || {
             if show.get() {
                 let video = video_ref
@@ -307,7 +632,7 @@ pub fn Video<F: Fn() -> bool + 'static>(
             }
             button(
                 class=format!("bg-slate-800 font-mono text-sm rounded px-2 py-1 block mx-auto my-4 transition-opacity {}",
-                    if show_replay_btn.get() && state.current_segment.get() != 0 { "opacity-100" } else { "invisible opacity-0" }
+                    if show_replay_btn.get() && state.current_segment.get() != 0 && !state.reversed.get() { "opacity-100" } else { "invisible opacity-0" }
                 ),
                 on:click=replay,
             ) {
@@ -317,47 +642,150 @@ pub fn Video<F: Fn() -> bool + 'static>(
     }
 }
 
+/// A slide video manifest in the manim-slides format.
 #[derive(Debug, Deserialize)]
-struct ManimSlides {
-    slides: Vec<ManimSlideData>,
+pub struct ManimSlides {
+    pub slides: Vec<ManimSlideData>,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Eq, Hash)]
-struct ManimSlideData {
-    file: String,
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ManimSlideData {
+    pub file: String,
+    /// The video played backwards, used when stepping back to the previous segment.
+    #[serde(default)]
+    pub rev_file: Option<String>,
     #[serde(rename = "loop")]
     r#loop: bool,
     auto_next: bool,
+    #[serde(default = "_playback_rate_default")]
+    playback_rate: f64,
+    #[serde(default = "_playback_rate_default")]
+    reversed_playback_rate: f64,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    dedent_notes: bool,
+}
+
+/// The URL that the video files in a manifest are relative to. The manifest is written by
+/// manim-slides as `<base>/slides/<name>.json` while the files are listed as `slides/files/...`, so
+/// this is the parent of the directory containing the manifest.
+pub fn manifest_base(video_json: &str) -> Option<&str> {
+    let (dir, _) = video_json.rsplit_once('/')?;
+    let (base, _) = dir.rsplit_once('/')?;
+    Some(base)
+}
+
+fn _playback_rate_default() -> f64 {
+    1.0
+}
+
+impl ManimSlideData {
+    /// The speaker notes, dedented if `dedent_notes` is set.
+    fn notes(&self) -> String {
+        if !self.dedent_notes {
+            return self.notes.clone();
+        }
+        let indent = self
+            .notes
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        self.notes
+            .lines()
+            .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
 }
 
 #[component(inline_props)]
-pub async fn ManimSlide(url_base: String, json_src: String) -> View {
-    let mut state = use_context::<SlideShowState>();
+pub async fn ManimSlide(
+    url_base: String,
+    json_src: String,
+    /// The index of the slide that this video belongs to.
+    slide: usize,
+    /// Called when the user wants to try loading the video again after an error.
+    #[prop(setter(transform = |f: impl Fn() + 'static| Box::new(f) as Box<dyn Fn()>))]
+    on_retry: Box<dyn Fn()>,
+) -> View {
+    let state = use_context::<SlideShowState>();
 
     // Fetch the file over HTTP and parse it.
-    let response = gloo_net::http::Request::get(&json_src)
-        .send()
-        .await
-        .expect("could not fetch slide"); // TODO: handle error
-    let slides: ManimSlides = response.json().await.expect("could not parse slide");
+    let slides: ManimSlides = match crate::fetch::fetch_json(&json_src).await {
+        Ok(slides) => slides,
+        Err(err) => {
+            return view! {
+                crate::fetch::FetchErrorView(what="video", error=err.to_string(), on_retry=on_retry)
+            };
+        }
+    };
+
+    let notes = slides.slides.iter().map(ManimSlideData::notes).collect();
+    state
+        .slides
+        .update(|slides| slides[slide].video_notes = notes);
+
+    // When stepping back to segment `i`, we play the reversed video of segment `i + 1` instead of
+    // jumping to the start of segment `i`. Its last frame is the same as the last frame of segment
+    // `i` so it can stay on screen. This does not work if segment `i` loops.
+    let segments = slides
+        .slides
+        .iter()
+        .enumerate()
+        .map(|(i, slide)| {
+            let reverse = slides
+                .slides
+                .get(i + 1)
+                .filter(|_| !slide.r#loop)
+                .and_then(|next| {
+                    let rev_file = next.rev_file.as_ref()?;
+                    Some((
+                        format!("{url_base}/{rev_file}"),
+                        next.reversed_playback_rate,
+                    ))
+                });
+            (i, slide.clone(), reverse)
+        })
+        .collect::<Vec<_>>();
 
     view! {
         div {
             Indexed(
-                list=slides.slides.into_iter().enumerate().collect::<Vec<_>>(),
-                view=move |(i, slide)| {
+                list=segments,
+                view=move |(i, slide, reverse)| {
                     let src = format!("{url_base}/{}", slide.file);
+                    let has_reverse = reverse.is_some();
+                    let reverse = match reverse {
+                        Some((rev_src, rate)) => view! {
+                            Video(
+                                video=rev_src,
+                                r#loop=false,
+                                playback_rate=rate,
+                                show=move || state.current_segment.get() == i && state.reversed.get(),
+                            )
+                        },
+                        None => view! {},
+                    };
                     view! {
                         Video(
                             video=src,
                             r#loop=slide.r#loop,
-                            show=move || state.current_segment.get() == i,
+                            playback_rate=slide.playback_rate,
+                            show=move || {
+                                state.current_segment.get() == i && !(has_reverse && state.reversed.get())
+                            },
                             on:ended=move |_| {
                                 if slide.auto_next {
-                                    state.current_segment += 1;
+                                    state.next_segment();
                                 }
                             },
                         )
+                        (reverse)
                     }
                 }
             )
@@ -367,7 +795,7 @@ pub async fn ManimSlide(url_base: String, json_src: String) -> View {
 
 #[component]
 pub fn SlideControls() -> View {
-    let mut state = use_context::<SlideShowState>();
+    let state = use_context::<SlideShowState>();
 
     let has_previous = move || state.current_slide.get() > 0;
     let has_next = move || state.current_slide.get() + 1 < state.slides.with(Vec::len);
@@ -389,13 +817,20 @@ pub fn SlideControls() -> View {
 
     let previous = move |_| {
         assert!(has_previous());
-        state.current_slide -= 1;
-        state.current_segment.set(0);
+        state.go_to(state.current_slide.get() - 1, 0);
     };
     let next = move |_| {
         assert!(has_next());
-        state.current_slide += 1;
-        state.current_segment.set(0);
+        state.go_to(state.current_slide.get() + 1, 0);
+    };
+    let open_presenter = move |_| {
+        let location = window().location();
+        let url = format!(
+            "{}?view=presenter{}",
+            location.pathname().unwrap(),
+            location.hash().unwrap()
+        );
+        let _ = window().open_with_url_and_target(&url, "_blank");
     };
 
     view! {
@@ -416,9 +851,89 @@ pub fn SlideControls() -> View {
                     "Next >"
                 }
             }
+            (if state.mode.get() == SlideShowMode::Audience {
+                view! {
+                    button(class="hidden md:inline flex-initial hover:underline mr-4", on:click=open_presenter) {
+                        "Presenter"
+                    }
+                }
+            } else {
+                view! {}
+            })
             span(class="flex-initial") {
                 (state.current_slide.get() + 1) " / " (state.slides.with(Vec::len))
             }
         }
     }
 }
+
+/// The side panel of the presenter view, with a timer, the speaker notes from the video and a
+/// preview of the next segment.
+#[component]
+fn PresenterPanel() -> View {
+    let state = use_context::<SlideShowState>();
+
+    let start = create_signal(0.0);
+    let elapsed = create_signal(0u64);
+    is_not_ssr! {
+        start.set(js_sys::Date::now());
+        let tick = Closure::<dyn FnMut()>::new(move || {
+            elapsed.set(((js_sys::Date::now() - start.get()) / 1000.0) as u64);
+        });
+        let handle = window()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                tick.as_ref().unchecked_ref(),
+                1000,
+            )
+            .unwrap();
+        on_cleanup(move || {
+            window().clear_interval_with_handle(handle);
+            drop(tick);
+        });
+    }
+    let reset_timer = move |_| {
+        start.set(js_sys::Date::now());
+        elapsed.set(0);
+    };
+    let timer = move || {
+        let elapsed = elapsed.get();
+        format!(
+            "{:02}:{:02}:{:02}",
+            elapsed / 3600,
+            elapsed / 60 % 60,
+            elapsed % 60
+        )
+    };
+
+    let notes = move || {
+        state.slides.with(|slides| {
+            slides
+                .get(state.current_slide.get())
+                .and_then(|slide| slide.video_notes.get(state.current_segment.get()))
+                .cloned()
+                .unwrap_or_default()
+        })
+    };
+
+    view! {
+        div(class="fixed top-0 right-0 h-full w-1/3 px-4 pt-16 pb-4 bg-slate-900 border-l-2 border-slate-700 overflow-y-auto flex flex-col gap-4 font-mono text-sm") {
+            div(class="flex flex-row justify-between items-center") {
+                span(class="text-2xl") { (timer) }
+                button(class="hover:underline", on:click=reset_timer) { "Reset" }
+            }
+            div {
+                "Slide " (state.current_slide.get() + 1) " / " (state.slides.with(Vec::len))
+                ", segment " (state.current_segment.get() + 1)
+            }
+            div {
+                h2(class="text-red-200 mb-2") { "Notes" }
+                p(class="whitespace-pre-wrap") { (notes) }
+            }
+            div {
+                h2(class="text-red-200 mb-2") { "Next" }
+                // Relative to the current page.
+                iframe(src="?view=next", class="w-full aspect-video pointer-events-none border border-slate-700 rounded")
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Heading anchors and the table of contents for prose posts.
+
+#[cfg_ssr]
+use std::cell::RefCell;
+use std::collections::VecDeque;
+#[cfg_ssr]
+use std::rc::Rc;
+
+use mdsycx::FromMd;
+use sycamore::prelude::*;
+
+use crate::pages::post::Heading;
+
+/// Context value for the ids of the headings in the post that have not been rendered yet. The
+/// heading components take their id from the front of the queue, in the order that they are
+/// rendered.
+#[derive(Debug, Clone, Copy)]
+struct HeadingIds(Signal<VecDeque<String>>);
+
+/// Context value for collecting the level and text of the headings rendered in the current scope.
+#[cfg_ssr]
+#[derive(Debug, Clone)]
+struct CollectHeadings(Rc<RefCell<Vec<(u8, String)>>>);
+
+/// Turn the heading text into an id, e.g. "CAD Design" becomes "cad-design".
+#[cfg_ssr]
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if (c.is_whitespace() || c == '-') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Collect the headings in the post by rendering its body, in the order that they are rendered.
+/// Every heading is given a unique id, with duplicates suffixed with `-1`, `-2`, etc.
+///
+/// The heading components take their ids in the same order when the post is rendered, so the ids
+/// match however the headings are written in the markdown.
+#[cfg_ssr]
+pub fn collect_headings(body: impl FnOnce() -> View + 'static) -> Vec<Heading> {
+    let collected = Rc::new(RefCell::new(Vec::new()));
+    sycamore::render_to_string({
+        let collected = collected.clone();
+        move || {
+            provide_context(CollectHeadings(collected));
+            body()
+        }
+    });
+
+    let mut counts = std::collections::HashMap::<String, usize>::new();
+    collected
+        .take()
+        .into_iter()
+        .map(|(level, text)| {
+            let slug = slugify(&text);
+            let slug = if slug.is_empty() {
+                "section".to_string()
+            } else {
+                slug
+            };
+            let count = counts.entry(slug.clone()).or_default();
+            let id = match *count {
+                0 => slug,
+                n => format!("{slug}-{n}"),
+            };
+            *count += 1;
+            Heading { level, text, id }
+        })
+        .collect()
+}
+
+/// Give the headings rendered in the current scope the ids of `headings`, which are collected with
+/// `collect_headings` at build time so that the ids are unique within the post and match the table
+/// of contents.
+pub fn provide_heading_ids(headings: &[Heading]) {
+    let ids = headings.iter().map(|heading| heading.id.clone()).collect();
+    provide_context(HeadingIds(create_signal(ids)));
+}
+
+fn next_heading_id() -> Option<String> {
+    let ids = try_use_context::<HeadingIds>()?;
+    ids.0.update(|ids| ids.pop_front())
+}
+
+#[derive(Props, FromMd)]
+pub struct HeadingProps {
+    pub children: Children,
+}
+
+/// Define a heading component that renders `$tag` with the next heading id and an anchor link to
+/// it. If the post does not provide heading ids, e.g. in the feed, a plain heading is rendered.
+/// While the headings are being collected, only the level and text are recorded.
+macro_rules! heading_component {
+    ($name:ident, $tag:ident, $level:literal) => {
+        #[component]
+        pub fn $name(props: HeadingProps) -> View {
+            is_ssr! {
+                if let Some(CollectHeadings(headings)) = try_use_context::<CollectHeadings>() {
+                    let text = crate::server_component::render_text(props.children);
+                    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    headings.borrow_mut().push(($level, text));
+                    return view! {};
+                }
+            }
+            let children = props.children.call();
+            match next_heading_id() {
+                Some(id) => {
+                    let href = format!("#{id}");
+                    view! {
+                        $tag(id=id) {
+                            (children)
+                            a(class="heading-anchor", href=href, aria-label="Link to this section") {
+                                "#"
+                            }
+                        }
+                    }
+                }
+                None => view! {
+                    $tag { (children) }
+                },
+            }
+        }
+    };
+}
+
+heading_component!(H1, h1, 1);
+heading_component!(H2, h2, 2);
+heading_component!(H3, h3, 3);
+heading_component!(H4, h4, 4);
+heading_component!(H5, h5, 5);
+heading_component!(H6, h6, 6);
+
+/// Distance from the top of the viewport below which a heading is considered to be scrolled past.
+#[cfg_not_ssr]
+const SCROLL_OFFSET: f64 = 80.0;
+
+/// Table of contents for a post, listing the second and third level headings. The section that is
+/// currently being read is highlighted.
+#[component(inline_props)]
+pub fn TableOfContents(headings: Vec<Heading>) -> View {
+    let headings = headings
+        .into_iter()
+        .filter(|heading| (2..=3).contains(&heading.level))
+        .collect::<Vec<_>>();
+    let active = create_signal(None::<String>);
+
+    is_not_ssr! {
+        let ids = headings
+            .iter()
+            .map(|heading| heading.id.clone())
+            .collect::<Vec<_>>();
+        // The active heading is the last one that has been scrolled past.
+        let update = move || {
+            let document = document();
+            let current = ids
+                .iter()
+                .filter(|id| {
+                    document
+                        .get_element_by_id(id)
+                        .is_some_and(|el| el.get_bounding_client_rect().top() <= SCROLL_OFFSET)
+                })
+                .last()
+                .cloned();
+            active.set(current);
+        };
+        super::add_window_listener("scroll", {
+            let update = update.clone();
+            move |_: web_sys::Event| update()
+        });
+        on_mount(update);
+    }
+
+    view! {
+        nav(class="sticky top-6 font-mono text-sm", aria-label="Table of contents") {
+            p(class="text-red-200 mb-2") { "Contents" }
+            ul(class="flex flex-col gap-1") {
+                Indexed(
+                    list=headings,
+                    view=move |heading| {
+                        let href = format!("#{}", heading.id);
+                        let indent = if heading.level > 2 { "ml-4" } else { "" };
+                        let text = heading.text;
+                        let id = heading.id;
+                        let class = move || {
+                            if active.with(|active| active.as_ref() == Some(&id)) {
+                                "text-red-200"
+                            } else {
+                                "text-gray-400 hover:text-gray-200"
+                            }
+                        };
+                        view! {
+                            li(class=indent) {
+                                a(class=class, href=href) { (text) }
+                            }
+                        }
+                    },
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Configuration of the site, read from `site.toml`.
+
+#[cfg_ssr]
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use sycamore::prelude::*;
+
+/// Id of the element in which the [`SiteConfig`] is embedded.
+pub static SITE_CONFIG_ID: &str = "site-config";
+
+/// Configuration of the site. This is loaded from `site.toml` during SSR and embedded into every
+/// page for the client.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SiteConfig {
+    /// The title of the site, used in page titles and feeds.
+    pub title: String,
+    /// The title shown in the header on wide screens and on the preview cards.
+    pub header_title: String,
+    /// The description of the site, used for pages that do not have their own description.
+    pub description: String,
+    pub author: String,
+    /// The URL where the site is deployed, without a trailing slash.
+    pub base_url: String,
+    /// The year shown in the copyright notice in the footer.
+    pub copyright_year: u32,
+    /// The number of posts on each page of the post list.
+    pub posts_per_page: usize,
+    #[serde(default)]
+    pub analytics: Option<AnalyticsConfig>,
+    /// The links shown in the header.
+    #[serde(default)]
+    pub nav: Vec<NavLink>,
+    /// Options for building the site. These are not needed by the client.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(default, skip_serializing)]
+    pub build: BuildConfig,
+}
+
+/// Analytics using [Umami](https://umami.is).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsConfig {
+    pub script: String,
+    pub website_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NavLink {
+    pub label: String,
+    pub href: String,
+}
+
+#[cfg_ssr]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildConfig {
+    /// The directory that the site is written to.
+    #[serde(default = "_out_dir_default")]
+    pub out_dir: PathBuf,
+    /// The directory where rendered pages are cached between builds. This should not be inside a
+    /// directory that Trunk watches.
+    #[serde(default = "_cache_dir_default")]
+    pub cache_dir: PathBuf,
+}
+
+#[cfg_ssr]
+impl Default for BuildConfig {
+    fn default() -> Self {
+        Self {
+            out_dir: _out_dir_default(),
+            cache_dir: _cache_dir_default(),
+        }
+    }
+}
+
+#[cfg_ssr]
+fn _out_dir_default() -> PathBuf {
+    PathBuf::from("dist/.stage")
+}
+
+#[cfg_ssr]
+fn _cache_dir_default() -> PathBuf {
+    PathBuf::from("target_ssr/render-cache")
+}
+
+static SITE: OnceLock<SiteConfig> = OnceLock::new();
+
+/// The configuration of the site.
+///
+/// During SSR, this is the configuration passed to [`init_site`], or `site.toml` if it was not
+/// called. On the client, this is read from the configuration that is embedded into every page.
+pub fn site() -> &'static SiteConfig {
+    SITE.get_or_init(|| {
+        is_ssr! {
+            load_site_config().unwrap_or_else(|err| panic!("{err}"))
+        }
+        is_not_ssr! {
+            crate::shell::read_embedded(SITE_CONFIG_ID)
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default()
+        }
+    })
+}
+
+/// Path of the configuration file. This can be changed with the `BLOG_SITE_CONFIG` environment
+/// variable, e.g. to use a different configuration for a staging deployment.
+#[cfg_ssr]
+pub fn site_config_path() -> PathBuf {
+    match std::env::var_os("BLOG_SITE_CONFIG") {
+        Some(path) => path.into(),
+        None => std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("site.toml"),
+    }
+}
+
+/// Read and parse the configuration file.
+#[cfg_ssr]
+pub fn load_site_config() -> Result<SiteConfig, String> {
+    let path = site_config_path();
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|err| format!("could not read `{}`: {err}", path.display()))?;
+    let config: SiteConfig =
+        toml::from_str(&contents).map_err(|err| format!("{}: {err}", path.display()))?;
+    if config.base_url.ends_with('/') {
+        return Err(format!(
+            "{}: `base_url` should not end with a `/`",
+            path.display()
+        ));
+    }
+    if config.posts_per_page == 0 {
+        return Err(format!(
+            "{}: `posts_per_page` should be at least 1",
+            path.display()
+        ));
+    }
+    Ok(config)
+}
+
+/// Use `config` as the configuration of the site. This needs to be called before the
+/// configuration is first used.
+#[cfg_ssr]
+pub fn init_site(config: SiteConfig) {
+    if SITE.set(config).is_err() {
+        panic!("site config was already loaded");
+    }
+}
@@ -0,0 +1,334 @@
+//! Generate Atom, RSS and JSON feeds for the posts.
+
+use std::fmt::{self, Write};
+use std::path::Path;
+use std::{fs, io};
+
+use sycamore::prelude::*;
+
+use crate::config::site;
+use crate::pages::post::{PostDate, PostLayout, PostMetadata};
+use crate::posts::POSTS;
+
+/// A post that is included in a feed.
+struct Entry {
+    meta: PostMetadata,
+    url: String,
+    /// The full rendered HTML of the post. This is `None` for posts that only make sense when
+    /// viewed on the website, such as slide shows.
+    content: Option<String>,
+}
+
+impl Entry {
+    /// The text to use as the summary of the post.
+    fn summary(&self) -> &str {
+        if self.meta.desc.is_empty() {
+            &self.meta.title
+        } else {
+            &self.meta.desc
+        }
+    }
+}
+
+/// A single feed, either of all the posts or of all the posts with a certain tag.
+struct Feed<'a> {
+    title: String,
+    /// Path of the HTML page that corresponds to this feed.
+    page: String,
+    /// Path of the feed without the extension.
+    path: String,
+    entries: Vec<&'a Entry>,
+}
+
+impl Feed<'_> {
+    fn updated(&self) -> PostDate {
+        self.entries
+            .iter()
+            .map(|entry| entry.meta.date)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Render the posts into feed entries, sorted by date descending.
+fn entries() -> Vec<Entry> {
+    let base_url = &site().base_url;
+    let mut entries = POSTS
+        .values()
+        .map(|post| {
+            let meta = post.front_matter.clone();
+            let content = match meta.layout {
+                PostLayout::Prose => {
+                    let body = post.body.clone();
+                    let render_math = meta.render_math;
+                    let html = sycamore::render_to_string(|| {
+                        provide_context(crate::components::math::RenderMath(render_math));
+                        view! {
+                            sycamore::web::NoHydrate {
+                                mdsycx::MDSycX(body=body, components=crate::pages::post::post_components())
+                            }
+                        }
+                    });
+                    Some(absolute_urls(&html))
+                }
+                PostLayout::Full => None,
+            };
+            Entry {
+                url: format!("{base_url}/post/{}", meta.filename),
+                meta,
+                content,
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.meta.date.cmp(&a.meta.date));
+    entries
+}
+
+/// Make root-relative URLs absolute since feed readers do not know where the post came from.
+fn absolute_urls(html: &str) -> String {
+    let base_url = &site().base_url;
+    html.replace(r#"href="/"#, &format!(r#"href="{base_url}/"#))
+        .replace(r#"src="/"#, &format!(r#"src="{base_url}/"#))
+}
+
+/// Escape text for use in XML.
+pub fn escape(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            '\'' => buf.push_str("&apos;"),
+            _ => buf.push(c),
+        }
+    }
+    buf
+}
+
+/// Format the date as an RFC 3339 timestamp.
+fn rfc3339(date: PostDate) -> String {
+    format!("{date}T00:00:00Z")
+}
+
+/// Format the date as an RFC 822 timestamp, as required by RSS.
+fn rfc822(date: PostDate) -> String {
+    static DAYS: &[&str] = &["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    static MONTHS: &[&str] = &[
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    // Sakamoto's method for finding the day of the week.
+    static OFFSETS: &[u32] = &[0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let month = date.month.clamp(1, 12);
+    let year = if month < 3 { date.year - 1 } else { date.year };
+    let weekday =
+        (year + year / 4 - year / 100 + year / 400 + OFFSETS[month as usize - 1] + date.day) % 7;
+
+    format!(
+        "{}, {:02} {} {:04} 00:00:00 +0000",
+        DAYS[weekday as usize],
+        date.day,
+        MONTHS[month as usize - 1],
+        date.year
+    )
+}
+
+/// Generate an Atom feed.
+fn atom(feed: &Feed) -> Result<String, fmt::Error> {
+    let base_url = &site().base_url;
+    let mut buf = String::new();
+
+    write!(
+        &mut buf,
+        r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns="http://www.w3.org/2005/Atom">"#
+    )?;
+    write!(&mut buf, "<title>{}</title>", escape(&feed.title))?;
+    write!(&mut buf, "<id>{base_url}{}</id>", feed.page)?;
+    write!(
+        &mut buf,
+        r#"<link rel="alternate" type="text/html" href="{base_url}{}"/>"#,
+        feed.page
+    )?;
+    write!(
+        &mut buf,
+        r#"<link rel="self" type="application/atom+xml" href="{base_url}{}.xml"/>"#,
+        feed.path
+    )?;
+    write!(&mut buf, "<updated>{}</updated>", rfc3339(feed.updated()))?;
+    write!(
+        &mut buf,
+        "<author><name>{}</name></author>",
+        escape(&site().author)
+    )?;
+
+    for entry in &feed.entries {
+        write!(&mut buf, "<entry>")?;
+        write!(&mut buf, "<title>{}</title>", escape(&entry.meta.title))?;
+        write!(&mut buf, "<id>{}</id>", entry.url)?;
+        write!(
+            &mut buf,
+            r#"<link rel="alternate" type="text/html" href="{}"/>"#,
+            entry.url
+        )?;
+        write!(
+            &mut buf,
+            "<published>{0}</published><updated>{0}</updated>",
+            rfc3339(entry.meta.date)
+        )?;
+        for tag in &entry.meta.tags {
+            write!(
+                &mut buf,
+                r#"<category term="{}" scheme="{base_url}/tags/"/>"#,
+                escape(tag)
+            )?;
+        }
+        write!(&mut buf, "<summary>{}</summary>", escape(entry.summary()))?;
+        if let Some(content) = &entry.content {
+            write!(
+                &mut buf,
+                r#"<content type="html">{}</content>"#,
+                escape(content)
+            )?;
+        }
+        write!(&mut buf, "</entry>")?;
+    }
+
+    write!(&mut buf, "</feed>")?;
+
+    Ok(buf)
+}
+
+/// Generate an RSS 2.0 feed.
+fn rss(feed: &Feed) -> Result<String, fmt::Error> {
+    let base_url = &site().base_url;
+    let mut buf = String::new();
+
+    write!(
+        &mut buf,
+        r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom"><channel>"#
+    )?;
+    write!(&mut buf, "<title>{}</title>", escape(&feed.title))?;
+    write!(&mut buf, "<link>{base_url}{}</link>", feed.page)?;
+    write!(
+        &mut buf,
+        r#"<atom:link rel="self" type="application/rss+xml" href="{base_url}/rss.xml"/>"#
+    )?;
+    write!(
+        &mut buf,
+        "<description>{}</description>",
+        escape(&feed.title)
+    )?;
+    write!(
+        &mut buf,
+        "<lastBuildDate>{}</lastBuildDate>",
+        rfc822(feed.updated())
+    )?;
+
+    for entry in &feed.entries {
+        write!(&mut buf, "<item>")?;
+        write!(&mut buf, "<title>{}</title>", escape(&entry.meta.title))?;
+        write!(&mut buf, "<link>{}</link>", entry.url)?;
+        write!(&mut buf, r#"<guid isPermaLink="true">{}</guid>"#, entry.url)?;
+        write!(&mut buf, "<pubDate>{}</pubDate>", rfc822(entry.meta.date))?;
+        for tag in &entry.meta.tags {
+            write!(
+                &mut buf,
+                r#"<category domain="{base_url}/tags/">{}</category>"#,
+                escape(tag)
+            )?;
+        }
+        let description = entry.content.as_deref().unwrap_or(entry.summary());
+        write!(
+            &mut buf,
+            "<description>{}</description>",
+            escape(description)
+        )?;
+        write!(&mut buf, "</item>")?;
+    }
+
+    write!(&mut buf, "</channel></rss>")?;
+
+    Ok(buf)
+}
+
+/// Generate a JSON feed (version 1.1).
+fn json(feed: &Feed) -> String {
+    let base_url = &site().base_url;
+    let items = feed
+        .entries
+        .iter()
+        .map(|entry| {
+            let mut item = serde_json::json!({
+                "id": entry.url,
+                "url": entry.url,
+                "title": entry.meta.title,
+                "summary": entry.summary(),
+                "date_published": rfc3339(entry.meta.date),
+                "tags": entry.meta.tags,
+            });
+            match &entry.content {
+                Some(content) => item["content_html"] = content.clone().into(),
+                None => item["content_text"] = entry.summary().into(),
+            }
+            item
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": feed.title,
+        "home_page_url": format!("{base_url}{}", feed.page),
+        "feed_url": format!("{base_url}{}.json", feed.path),
+        "authors": [{ "name": site().author }],
+        "items": items,
+    })
+    .to_string()
+}
+
+/// Write the feeds into `out_dir`.
+///
+/// This writes `feed.xml` (Atom), `rss.xml` and `feed.json` for all the posts, as well as an Atom
+/// feed at `tags/<tag>.xml` for every tag.
+pub fn write_feeds(out_dir: &Path) -> io::Result<()> {
+    let to_io = |_: fmt::Error| io::Error::other("failed to format feed");
+
+    let entries = entries();
+
+    let all = Feed {
+        title: site().title.clone(),
+        page: "/".to_string(),
+        path: "/feed".to_string(),
+        entries: entries.iter().collect(),
+    };
+    fs::write(out_dir.join("feed.xml"), atom(&all).map_err(to_io)?)?;
+    fs::write(out_dir.join("rss.xml"), rss(&all).map_err(to_io)?)?;
+    fs::write(out_dir.join("feed.json"), json(&all))?;
+
+    let mut tags = entries
+        .iter()
+        .flat_map(|entry| entry.meta.tags.iter())
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+
+    fs::create_dir_all(out_dir.join("tags"))?;
+    for tag in tags {
+        let feed = Feed {
+            title: format!("{} - #{tag}", site().title),
+            page: format!("/tags/{tag}"),
+            path: format!("/tags/{tag}"),
+            entries: entries
+                .iter()
+                .filter(|entry| entry.meta.tags.contains(tag))
+                .collect(),
+        };
+        fs::write(
+            out_dir.join("tags").join(format!("{tag}.xml")),
+            atom(&feed).map_err(to_io)?,
+        )?;
+    }
+
+    Ok(())
+}
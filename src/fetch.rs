@@ -0,0 +1,87 @@
+//! Fetching resources on the client with error handling.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use sycamore::prelude::*;
+
+/// An error that occurred while fetching a resource.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The request could not be sent, e.g. because of a network error.
+    Network(gloo_net::Error),
+    /// The server responded with a non-2xx status code.
+    Status { status: u16, status_text: String },
+    /// The response body could not be read or parsed.
+    Body(gloo_net::Error),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(err) => write!(f, "network error: {err}"),
+            Self::Status {
+                status,
+                status_text,
+            } => write!(f, "server responded with {status} {status_text}"),
+            Self::Body(err) => write!(f, "invalid response: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Send a GET request, returning an error if the response does not have a 2xx status code.
+async fn get(url: &str) -> Result<gloo_net::http::Response, FetchError> {
+    let response = gloo_net::http::Request::get(url)
+        .send()
+        .await
+        .map_err(FetchError::Network)?;
+    if !response.ok() {
+        return Err(FetchError::Status {
+            status: response.status(),
+            status_text: response.status_text(),
+        });
+    }
+    Ok(response)
+}
+
+/// Log the error to the console if there is one.
+fn log_error<T>(url: &str, result: Result<T, FetchError>) -> Result<T, FetchError> {
+    if let Err(err) = &result {
+        web_sys::console::error_1(&format!("could not fetch `{url}`: {err}").into());
+    }
+    result
+}
+
+/// Fetch a resource as text.
+#[cfg_not_ssr]
+pub async fn fetch_text(url: &str) -> Result<String, FetchError> {
+    let result = async { get(url).await?.text().await.map_err(FetchError::Body) }.await;
+    log_error(url, result)
+}
+
+/// Fetch a resource and parse it as JSON.
+pub async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, FetchError> {
+    let result = async { get(url).await?.json().await.map_err(FetchError::Body) }.await;
+    log_error(url, result)
+}
+
+/// Display an error that occurred while fetching a resource, with a button to try again.
+#[component(inline_props)]
+pub fn FetchErrorView(
+    /// What was being loaded, e.g. "video".
+    what: &'static str,
+    error: String,
+    #[prop(setter(transform = |f: impl Fn() + 'static| Box::new(f) as Box<dyn Fn()>))]
+    on_retry: Box<dyn Fn()>,
+) -> View {
+    view! {
+        div(class="border-l-4 border-red-500 bg-red-950 p-2 my-4 rounded font-mono text-sm") {
+            p { "Could not load " (what) ": " (error) }
+            button(class="hover:underline text-red-200", on:click=move |_| on_retry()) {
+                "Retry"
+            }
+        }
+    }
+}
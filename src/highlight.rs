@@ -0,0 +1,39 @@
+//! Syntax highlighting for code blocks at build-time.
+
+use std::sync::LazyLock;
+
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+/// Prefix the classes to avoid clashing with the Tailwind classes.
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+/// The theme used for generating the stylesheet.
+static THEME: &str = "base16-eighties.dark";
+
+/// Highlight the code, returning HTML with class-annotated spans. Falls back to plain text if the
+/// language is unknown.
+pub fn highlight(code: &str, lang: &str) -> String {
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+    let mut generator =
+        ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAX_SET, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .expect("failed to highlight code");
+    }
+    generator.finalize()
+}
+
+/// Generate the stylesheet for the highlighted code.
+pub fn theme_css() -> String {
+    let themes = ThemeSet::load_defaults();
+    css_for_theme_with_class_style(&themes.themes[THEME], CLASS_STYLE)
+        .expect("failed to generate highlighting theme")
+}
@@ -0,0 +1,219 @@
+//! Check that the internal links and assets in the generated site resolve.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::{fs, io};
+
+use scraper::{Html, Selector};
+use sycamore_router::Route;
+use walkdir::WalkDir;
+
+use crate::Routes;
+
+/// Files that Trunk adds to the output directory. These are missing when the site is generated
+/// without Trunk, so links to them are not checked.
+static TRUNK_FILES: &[&str] = &["/blog.js", "/blog_bg.wasm", "/index.css"];
+
+/// Directories that Trunk copies into the output directory, as pairs of the URL path that they are
+/// served under and the source directory. Links into these are also resolved against the source
+/// directory for the same reason.
+static COPIED_DIRS: &[(&str, &str)] = &[("/assets", "assets")];
+
+/// A link in a generated page that does not resolve.
+#[derive(Debug, Clone)]
+pub struct BrokenLink {
+    /// Path of the page containing the link, relative to the output directory.
+    pub page: String,
+    pub url: String,
+    pub reason: String,
+}
+
+/// A page that was parsed from the output directory.
+struct Page {
+    /// The `href` and `src` attributes in the page.
+    links: Vec<String>,
+    /// All the `id`s in the page that can be used as fragments.
+    ids: HashSet<String>,
+}
+
+fn parse_page(html: &str) -> Page {
+    static LINKS: LazyLock<Selector> = LazyLock::new(|| Selector::parse("[href], [src]").unwrap());
+    static IDS: LazyLock<Selector> = LazyLock::new(|| Selector::parse("[id], a[name]").unwrap());
+
+    let html = Html::parse_document(html);
+    let links = html
+        .select(&LINKS)
+        .flat_map(|el| [el.attr("href"), el.attr("src")])
+        .flatten()
+        .map(ToString::to_string)
+        .collect();
+    let ids = html
+        .select(&IDS)
+        .flat_map(|el| [el.attr("id"), el.attr("name")])
+        .flatten()
+        .map(ToString::to_string)
+        .collect();
+    Page { links, ids }
+}
+
+/// Whether the URL points to another site or is not a link at all, e.g. `mailto:`.
+fn is_external(url: &str) -> bool {
+    url.starts_with("//")
+        || url.split_once(':').is_some_and(|(scheme, _)| {
+            scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        })
+}
+
+/// Decode `%XX` escapes in a URL path.
+pub fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Resolve a URL path to a file in the output directory. Like on the deployed site, `/foo` can be
+/// served by `foo`, `foo.html` or `foo/index.html`.
+pub fn resolve(out_dir: &Path, path: &str) -> Option<String> {
+    let path = path.trim_matches('/');
+    let candidates = [
+        path.to_string(),
+        format!("{path}.html"),
+        format!("{path}/index.html")
+            .trim_start_matches('/')
+            .to_string(),
+    ];
+    candidates
+        .into_iter()
+        .find(|candidate| !candidate.is_empty() && out_dir.join(candidate).is_file())
+}
+
+/// Whether the URL path points to a file that Trunk adds to the output directory, see
+/// [`TRUNK_FILES`] and [`COPIED_DIRS`].
+fn is_trunk_file(path: &str) -> bool {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    TRUNK_FILES.contains(&path)
+        || COPIED_DIRS.iter().any(|(prefix, dir)| {
+            path.strip_prefix(prefix)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .is_some_and(|rest| root.join(dir).join(rest).is_file())
+        })
+}
+
+/// Parse every HTML file in `out_dir` and check that all the internal links and assets resolve to
+/// a file in `out_dir` or to a file that Trunk adds to it, and that links with fragments point to
+/// an existing `id`.
+pub fn check_links(out_dir: &Path) -> io::Result<Vec<BrokenLink>> {
+    let mut pages = HashMap::new();
+    for entry in WalkDir::new(out_dir) {
+        let entry = entry?;
+        if entry.path().extension().is_none_or(|ext| ext != "html") {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(out_dir)
+            .expect("should be inside out dir")
+            .to_string_lossy()
+            .replace('\\', "/");
+        let html = fs::read_to_string(entry.path())?;
+        pages.insert(path, parse_page(&html));
+    }
+
+    let mut broken = Vec::new();
+    for (page, Page { links, .. }) in &pages {
+        for url in links {
+            if is_external(url) {
+                continue;
+            }
+            let mut error = |reason: String| {
+                broken.push(BrokenLink {
+                    page: page.clone(),
+                    url: url.clone(),
+                    reason,
+                })
+            };
+
+            let (rest, fragment) = url.split_once('#').unwrap_or((url, ""));
+            let path = rest.split_once('?').map_or(rest, |(path, _)| path);
+            let path = percent_decode(path);
+
+            let target = if path.is_empty() {
+                page.clone()
+            } else {
+                // Resolve relative URLs against the directory of the page.
+                let path = if path.starts_with('/') {
+                    path
+                } else {
+                    let dir = Path::new(page).parent().unwrap_or(Path::new(""));
+                    format!("/{}", PathBuf::from(dir).join(&path).to_string_lossy())
+                };
+                match resolve(out_dir, &path) {
+                    Some(target) => target,
+                    None if is_trunk_file(&path) => continue,
+                    None => {
+                        error(match Routes::NotFound.match_path(&path) {
+                            Routes::NotFound => format!("`{path}` does not exist"),
+                            _ => format!("`{path}` matches a route but was not generated"),
+                        });
+                        continue;
+                    }
+                }
+            };
+
+            if !fragment.is_empty() {
+                if let Some(target_page) = pages.get(&target) {
+                    if !target_page.ids.contains(&percent_decode(fragment)) {
+                        error(format!("`{target}` has no element with id `{fragment}`"));
+                    }
+                }
+            }
+        }
+    }
+    broken.sort_by(|a, b| (&a.page, &a.url).cmp(&(&b.page, &b.url)));
+    Ok(broken)
+}
+
+/// A human readable name for the source of a page, e.g. the post it was generated from.
+fn page_source(page: &str) -> String {
+    match page
+        .strip_prefix("post/")
+        .and_then(|page| page.strip_suffix(".html"))
+    {
+        Some(id) => format!("post `{id}`"),
+        None => format!("page `/{page}`"),
+    }
+}
+
+/// Print the broken links, grouped by the page that they were found in.
+pub fn report(broken: &[BrokenLink]) {
+    let mut by_page = BTreeMap::<_, Vec<_>>::new();
+    for link in broken {
+        by_page.entry(&link.page).or_default().push(link);
+    }
+    for (page, links) in by_page {
+        eprintln!("warning: broken links in {}:", page_source(page));
+        for link in links {
+            eprintln!("  {}: {}", link.url, link.reason);
+        }
+    }
+}
@@ -1,5 +1,24 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod build_cache;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
 mod components;
+mod config;
+#[cfg(not(target_arch = "wasm32"))]
+mod feed;
+mod fetch;
+#[cfg(not(target_arch = "wasm32"))]
+mod highlight;
+#[cfg(not(target_arch = "wasm32"))]
+mod links;
+#[cfg(not(target_arch = "wasm32"))]
+mod og_image;
 mod pages;
+#[cfg(not(target_arch = "wasm32"))]
+mod posts;
+mod search;
+#[cfg(not(target_arch = "wasm32"))]
+mod serve;
 mod server_component;
 mod shell;
 
@@ -10,10 +29,22 @@ use sycamore_router::Route;
 pub enum Routes {
     #[to("/")]
     Home,
+    #[to("/page/<n>")]
+    Page(usize),
+    #[to("/archive")]
+    Archive,
     #[to("/about")]
     About,
     #[to("/post/<id>")]
     Post(String),
+    #[to("/tags")]
+    Tags,
+    #[to("/tags/<tag>")]
+    Tag(String),
+    #[to("/series/<name>")]
+    Series(String),
+    #[to("/search")]
+    Search,
     #[not_found]
     NotFound,
 }
@@ -38,60 +69,266 @@ fn main() {
 #[cfg_ssr]
 #[tokio::main]
 async fn main() {
-    use std::{fs, path::PathBuf};
+    use clap::Parser;
 
-    static PUBLIC_PATH: &str = "dist/.stage";
+    // Running without a command builds the site, which is what the Trunk hook does.
+    let command = cli::Cli::parse()
+        .command
+        .unwrap_or_else(|| cli::Command::Build(Default::default()));
+    if let Err(err) = run(command).await {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
 
-    for (route, path) in get_static_paths() {
-        let path = path.trim_start_matches('/');
-        let path = PathBuf::from(PUBLIC_PATH).join(path);
+/// Load the site configuration, applying the options of the command, and run the command.
+#[cfg_ssr]
+async fn run(command: cli::Command) -> Result<(), String> {
+    use cli::Command;
 
-        eprintln!("Rendering `{}`", path.display());
+    let mut config = config::load_site_config()?;
+    match &command {
+        Command::Build(args) => {
+            if let Some(out_dir) = &args.out_dir {
+                config.build.out_dir = out_dir.clone();
+            }
+            if let Some(base_url) = &args.base_url {
+                config.base_url = base_url.trim_end_matches('/').to_string();
+            }
+            if args.drafts {
+                pages::post::include_drafts();
+            }
+        }
+        Command::List => pages::post::include_drafts(),
+        _ => {}
+    }
+    config::init_site(config);
+
+    match command {
+        Command::Build(args) => build_site(args.force).await,
+        Command::Serve(args) => serve::serve(&args.dir, args.port)
+            .await
+            .map_err(|err| err.to_string())?,
+        Command::New { slug } => cli::new_post(&slug)?,
+        Command::Check => cli::check()?,
+        Command::List => cli::list(),
+    }
+    Ok(())
+}
 
-        let html = sycamore::render_to_string_await_suspense(|| {
+/// Render the page for `route` to HTML, along with the server components rendered in it.
+#[cfg_ssr]
+async fn render_page(route: Routes) -> (String, Vec<(String, String)>) {
+    let components = server_component::ServerComponents::default();
+    let html = sycamore::render_to_string_await_suspense({
+        let components = components.clone();
+        move || {
+            provide_context(components);
             view! {
                 shell::Shell {
                     sycamore_router::StaticRouter(route=route, view=shell::App)
                 }
             }
-        })
-        .await;
+        }
+    })
+    .await;
+    (format!("<!DOCTYPE html>{html}"), components.take())
+}
 
-        let dir = path.parent().expect("failed to get parent dir");
-        fs::create_dir_all(dir).expect("failed to create parent dir");
-        fs::write(path, format!("<!DOCTYPE html>{html}")).expect("failed to write html file");
+/// Everything that the page for `route` is rendered from other than the code and the site
+/// configuration. This is used to check whether the page has changed since the last build.
+#[cfg_ssr]
+fn page_inputs(route: &Routes) -> String {
+    match route {
+        // A post only shows its own page, which includes the posts that it links to. Whether a post
+        // is published depends on the current date, so it is not part of its source.
+        Routes::Post(id) => {
+            let page = posts::post_page(id);
+            let published = page.as_ref().map(|page| page.post.is_published());
+            serde_json::to_string(&(posts::post_source(id), page, published))
+                .expect("failed to serialize page inputs")
+        }
+        // The summaries only include the posts that are visible and whether they are published.
+        route if shell::lists_posts(route) => posts::post_index_json(),
+        // The other pages do not show any posts.
+        _ => String::new(),
+    }
+}
+
+/// Render the page for `route` into `file`, relative to the output directory, unless it can be
+/// restored from the cache. Returns how long rendering took, or `None` if the page was restored.
+///
+/// The rendering futures are not `Send`, so this runs on a blocking thread with its own runtime.
+#[cfg_ssr]
+fn build_page(
+    route: Routes,
+    file: String,
+    public_path: &std::path::Path,
+    cache: &build_cache::BuildCache,
+) -> Option<std::time::Duration> {
+    use std::fs;
+
+    let hash = cache.page_hash(&file, &page_inputs(&route));
+    if cache.restore(&file, hash, public_path) {
+        return None;
     }
 
-    let mut server_components = server_component::SERVER_COMPONENTS.lock().unwrap();
-    for (id, html) in server_components.drain() {
-        let path = PathBuf::from(PUBLIC_PATH)
-            .join("server_components")
-            .join(format!("{}.html", id));
+    let start = std::time::Instant::now();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to create runtime");
+    let (html, components) = runtime.block_on(render_page(route.clone()));
+    let mut files = vec![(file, html)];
 
-        eprintln!("Rendering server component `{id}` to `{}`", path.display());
+    // Write out the post page so that the client can fetch it when navigating to the post. The
+    // server fragments are recorded while rendering the page.
+    if let Routes::Post(id) = &route {
+        let mut page = posts::post_page(id).expect("post should exist");
+        page.fragments = server_component::take_fragments(&format!("post/{id}"));
+        let json = serde_json::to_string(&page).expect("failed to serialize post page");
+        files.push((format!("post/{id}.json"), json));
+    }
+    for (id, html) in components {
+        files.push((format!("server_components/{id}.html"), html));
+    }
 
+    for (file, contents) in &files {
+        let path = public_path.join(file);
         let dir = path.parent().expect("failed to get parent dir");
         fs::create_dir_all(dir).expect("failed to create parent dir");
-        fs::write(path, html).expect("failed to write html file");
+        fs::write(path, contents).expect("failed to write file");
     }
+    if let Err(err) = cache.store(&files[0].0, hash, &files) {
+        eprintln!("warning: could not cache `{}`: {err}", files[0].0);
+    }
+    Some(start.elapsed())
+}
+
+/// Generate the site into the output directory. Pages are rendered in parallel, and pages that
+/// have not changed since the last build are copied from the build cache unless `force` is set.
+#[cfg_ssr]
+async fn build_site(force: bool) {
+    use std::fs;
+    use std::sync::Arc;
+
+    let public_path = config::site().build.out_dir.clone();
+
+    let errors = posts::validate_posts();
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("error: {error}");
+        }
+        eprintln!(
+            "could not build site due to {} error(s) in posts",
+            errors.len()
+        );
+        std::process::exit(1);
+    }
+
+    let cache = Arc::new(build_cache::BuildCache::load(
+        &config::site().build.cache_dir,
+        force,
+    ));
+
+    let start = std::time::Instant::now();
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let permits = Arc::new(tokio::sync::Semaphore::new(threads));
+    let mut tasks = tokio::task::JoinSet::new();
+    for (route, path) in get_static_paths() {
+        let file = path.trim_start_matches('/').to_string();
+        let public_path = public_path.clone();
+        let cache = cache.clone();
+        let permits = permits.clone();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await.unwrap();
+            tokio::task::spawn_blocking(move || {
+                build_page(route, file.clone(), &public_path, &cache).map(|elapsed| (file, elapsed))
+            })
+            .await
+            .expect("failed to render page")
+        });
+    }
+
+    let (mut rendered, mut restored) = (0, 0);
+    while let Some(result) = tasks.join_next().await {
+        match result.expect("failed to render page") {
+            Some((file, elapsed)) => {
+                eprintln!("Rendered `{file}` in {elapsed:.1?}");
+                rendered += 1;
+            }
+            None => restored += 1,
+        }
+    }
+    eprintln!(
+        "Rendered {rendered} page(s) and reused {restored} unchanged page(s) in {:.1?}",
+        start.elapsed()
+    );
+    if let Err(err) = cache.save() {
+        eprintln!("warning: could not save build cache: {err}");
+    }
+
+    eprintln!("Generating post index");
+    fs::write(
+        public_path.join(pages::post::POST_INDEX_PATH.trim_start_matches('/')),
+        posts::post_index_json(),
+    )
+    .expect("failed to write post index");
+
+    eprintln!("Generating highlight.css");
+    fs::write(public_path.join("highlight.css"), highlight::theme_css())
+        .expect("failed to write highlight.css");
 
     eprintln!("Generating sitemap.xml");
     let sitemap = generate_sitemap_xml().expect("failed to generate sitemap");
-    fs::write(PathBuf::from(PUBLIC_PATH).join("sitemap.xml"), sitemap)
-        .expect("failed to write sitemap.xml");
+    fs::write(public_path.join("sitemap.xml"), sitemap).expect("failed to write sitemap.xml");
+
+    eprintln!("Generating preview images");
+    og_image::write_cards(&public_path).expect("failed to write preview images");
+
+    eprintln!("Generating feeds");
+    feed::write_feeds(&public_path).expect("failed to write feeds");
+
+    eprintln!("Generating search index");
+    search::write_index(&public_path).expect("failed to write search index");
+
+    // This needs to run last so that all the files have been written.
+    eprintln!("Checking links");
+    let broken = links::check_links(&public_path).expect("failed to check links");
+    links::report(&broken);
+    // Broken links are only warnings unless `BLOG_STRICT_LINKS` is set.
+    if !broken.is_empty() && std::env::var_os("BLOG_STRICT_LINKS").is_some() {
+        eprintln!("found {} broken link(s)", broken.len());
+        std::process::exit(1);
+    }
 }
 
 pub fn get_static_paths() -> Vec<(Routes, String)> {
     let mut paths = vec![];
 
     paths.push((Routes::Home, "/index.html".to_string()));
+    // The first page is the home page.
+    for n in 2..=pages::home::page_count() {
+        paths.push((Routes::Page(n), format!("/page/{n}.html")));
+    }
+    paths.push((Routes::Archive, "/archive.html".to_string()));
     paths.push((Routes::About, "/about.html".to_string()));
     paths.push((Routes::NotFound, "/404.html".to_string()));
+    paths.push((Routes::Search, "/search.html".to_string()));
 
-    for post in pages::post::POSTS.keys() {
+    for post in pages::post::POST_INDEX.keys() {
         paths.push((Routes::Post(post.clone()), format!("/post/{post}.html")));
     }
 
+    paths.push((Routes::Tags, "/tags.html".to_string()));
+    for (tag, _) in pages::tags::tag_counts() {
+        paths.push((Routes::Tag(tag.clone()), format!("/tags/{tag}.html")));
+    }
+
+    for name in pages::series::all_series() {
+        paths.push((Routes::Series(name.clone()), format!("/series/{name}.html")));
+    }
+
     paths
 }
 
@@ -99,8 +336,6 @@ pub fn get_static_paths() -> Vec<(Routes, String)> {
 pub fn generate_sitemap_xml() -> Result<String, std::fmt::Error> {
     use std::fmt::Write;
 
-    static BASE_URL: &str = "https://lukechu.dev";
-
     let paths = get_static_paths();
     let mut buf = String::new();
 
@@ -110,14 +345,15 @@ pub fn generate_sitemap_xml() -> Result<String, std::fmt::Error> {
     )?;
 
     for (route, path) in paths {
-        if route == Routes::NotFound {
+        // The search page only shows results for a query, so there is nothing to index.
+        if matches!(route, Routes::NotFound | Routes::Search) {
             continue;
         }
         let path = path
             .strip_suffix(".html")
             .expect("should be an html page")
             .trim_end_matches("index");
-        let loc = format!("{BASE_URL}{path}");
+        let loc = format!("{}{path}", config::site().base_url);
 
         write!(&mut buf, r#"<url><loc>{loc}</loc></url>"#)?;
     }
@@ -0,0 +1,117 @@
+//! Generate the Open Graph preview images for posts.
+
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+use std::{fs, io};
+
+use resvg::{tiny_skia, usvg};
+
+use crate::config::site;
+use crate::feed::escape;
+use crate::pages::post::PostMetadata;
+use crate::posts::POSTS;
+
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+
+/// Maximum number of characters of the title on a single line, and the maximum number of lines.
+const TITLE_LINE_LEN: usize = 26;
+const TITLE_MAX_LINES: usize = 3;
+
+/// The fonts used for the cards. These are the same fonts that are used on the site, decompressed
+/// from WOFF2 since that is not supported by the font database.
+static FONTS: LazyLock<Arc<usvg::fontdb::Database>> = LazyLock::new(|| {
+    static FILES: &[&[u8]] = &[
+        include_bytes!("../assets/fonts/eb-garamond-v27-latin-regular.woff2"),
+        include_bytes!("../assets/fonts/eb-garamond-v27-latin-700.woff2"),
+        include_bytes!("../assets/fonts/FiraCode-VF.woff2"),
+    ];
+
+    let mut fontdb = usvg::fontdb::Database::new();
+    for file in FILES {
+        let ttf = wuff::decompress_woff2(file).expect("failed to decompress font");
+        fontdb.load_font_data(ttf);
+    }
+    Arc::new(fontdb)
+});
+
+/// Split the title into lines that fit on the card, adding an ellipsis if it is too long.
+fn wrap_title(title: &str) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+    for word in title.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= TITLE_LINE_LEN => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    if lines.len() > TITLE_MAX_LINES {
+        lines.truncate(TITLE_MAX_LINES);
+        lines[TITLE_MAX_LINES - 1].push('…');
+    }
+    lines
+}
+
+/// Generate the SVG for the card, using the same colors as the site.
+fn card_svg(meta: &PostMetadata) -> String {
+    let title = wrap_title(&meta.title)
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            format!(
+                r#"<tspan x="80" y="{}">{}</tspan>"#,
+                260 + i * 80,
+                escape(line)
+            )
+        })
+        .collect::<String>();
+    let tags = meta
+        .tags
+        .iter()
+        .map(|tag| format!("#{tag}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}">
+<rect width="100%" height="100%" fill="#020617"/>
+<rect width="100%" height="12" fill="#fecaca"/>
+<text x="80" y="120" font-family="Fira Code" font-size="32" font-weight="bold" fill="#fecaca">{header}</text>
+<text font-family="EB Garamond" font-size="72" font-weight="bold" fill="#e2e8f0">{title}</text>
+<text x="80" y="530" font-family="Fira Code" font-size="28" fill="#94a3b8">{date}</text>
+<text x="1120" y="530" text-anchor="end" font-family="Fira Code" font-size="28" fill="#fca5a5">{tags}</text>
+</svg>"##,
+        header = escape(&site().header_title),
+        date = meta.date,
+        tags = escape(&tags),
+    )
+}
+
+/// Render the card for the post to a PNG.
+fn render_card(meta: &PostMetadata) -> Result<Vec<u8>, String> {
+    let options = usvg::Options {
+        fontdb: Arc::clone(&FONTS),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(&card_svg(meta), &options).map_err(|err| err.to_string())?;
+    let mut pixmap = tiny_skia::Pixmap::new(WIDTH, HEIGHT).expect("card should not be empty");
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|err| err.to_string())
+}
+
+/// Write a card for every post that does not have a `cover` image into `out_dir` at
+/// `post/<id>.png`, next to the post HTML.
+pub fn write_cards(out_dir: &Path) -> io::Result<()> {
+    for (id, post) in POSTS.iter() {
+        if post.front_matter.cover.is_some() {
+            continue;
+        }
+        let png = render_card(&post.front_matter).map_err(io::Error::other)?;
+        let dir = out_dir.join("post");
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(format!("{id}.png")), png)?;
+    }
+    Ok(())
+}
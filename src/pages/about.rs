@@ -1,10 +1,11 @@
 use sycamore::prelude::*;
 
+use crate::config::site;
 use crate::shell::set_title;
 
 #[component]
 pub fn About() -> View {
-    set_title("About - lukechu");
+    set_title(format!("About - {}", site().title));
     view! {
         div(class="post-content max-w-prose mx-auto") {
             h1 { "About" }
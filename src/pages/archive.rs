@@ -0,0 +1,94 @@
+use sycamore::prelude::*;
+
+use crate::{
+    config::site,
+    pages::post::{posts_by_date, PostSummary},
+    shell::{set_description, set_title},
+};
+
+static MONTHS: &[&str] = &[
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// The posts grouped by year and then by month, most recent first.
+pub fn posts_by_month() -> Vec<(u32, Vec<(u32, Vec<PostSummary>)>)> {
+    let mut years = Vec::<(u32, Vec<(u32, Vec<PostSummary>)>)>::new();
+    for post in posts_by_date() {
+        if years.last().is_none_or(|(year, _)| *year != post.date.year) {
+            years.push((post.date.year, Vec::new()));
+        }
+        let (_, months) = years.last_mut().unwrap();
+        if months
+            .last()
+            .is_none_or(|(month, _)| *month != post.date.month)
+        {
+            months.push((post.date.month, Vec::new()));
+        }
+        months.last_mut().unwrap().1.push(post);
+    }
+    years
+}
+
+/// A list of all the posts, grouped by year and month.
+#[component]
+pub fn Archive() -> View {
+    set_title(format!("Archive - {}", site().title));
+    set_description("All the posts on this site, grouped by month.");
+
+    let years = posts_by_month()
+        .into_iter()
+        .map(|(year, months)| {
+            let year = year.to_string();
+            let months = months
+                .into_iter()
+                .map(|(month, posts)| {
+                    let month = month
+                        .checked_sub(1)
+                        .and_then(|i| MONTHS.get(i as usize))
+                        .map_or_else(|| month.to_string(), ToString::to_string);
+                    view! {
+                        h3 { (month) }
+                        ul(class="mb-4") {
+                            Indexed(
+                                list=posts,
+                                view=|post| {
+                                    let href = format!("/post/{}", post.id);
+                                    let day = format!("{:02} ", post.date.day);
+                                    let title = post.title;
+                                    view! {
+                                        li {
+                                            span(class="text-sm text-gray-400 font-mono") { (day) }
+                                            a(href=href) { (title) }
+                                        }
+                                    }
+                                },
+                            )
+                        }
+                    }
+                })
+                .collect::<Vec<View>>();
+            view! {
+                h2 { (year) }
+                (months)
+            }
+        })
+        .collect::<Vec<View>>();
+
+    view! {
+        div(class="post-content max-w-prose mx-auto") {
+            h1 { "Archive" }
+            (years)
+        }
+    }
+}
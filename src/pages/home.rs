@@ -1,43 +1,116 @@
 use sycamore::prelude::*;
 
-use crate::{pages::post::POSTS, shell::set_title};
+use crate::{
+    config::site,
+    pages::post::{posts_by_date, PostSummary},
+    shell::set_title,
+};
+
+/// The number of pages that the posts on the home page are split into.
+pub fn page_count() -> usize {
+    posts_by_date().len().div_ceil(site().posts_per_page).max(1)
+}
 
 #[component]
 pub fn Home() -> View {
-    set_title("lukechu");
     view! {
-        PostList()
+        HomePage(page=1)
     }
 }
 
-#[component]
-fn PostList() -> View {
-    let mut posts = POSTS
-        .values()
-        .map(|x| x.front_matter.clone())
+/// A page of the list of all the posts. The first page is the home page.
+#[component(inline_props)]
+pub fn HomePage(page: usize) -> View {
+    let count = page_count();
+    if page == 0 || page > count {
+        return view! {
+            crate::shell::NotFound()
+        };
+    }
+
+    if page == 1 {
+        set_title(site().title.clone());
+    } else {
+        set_title(format!("Page {page} - {}", site().title));
+    }
+    let posts = posts_by_date()
+        .into_iter()
+        .skip((page - 1) * site().posts_per_page)
+        .take(site().posts_per_page)
         .collect::<Vec<_>>();
-    // Sort posts by date descending.
-    posts.sort_by_key(|post| post.date);
+
+    let newer = match page {
+        1 => view! {
+            span {}
+        },
+        2 => view! {
+            a(class="hover:underline", href="/") { "← Newer posts" }
+        },
+        _ => {
+            let href = format!("/page/{}", page - 1);
+            view! {
+                a(class="hover:underline", href=href) { "← Newer posts" }
+            }
+        }
+    };
+    let older = if page < count {
+        let href = format!("/page/{}", page + 1);
+        view! {
+            a(class="hover:underline", href=href) { "Older posts →" }
+        }
+    } else {
+        view! {
+            span {}
+        }
+    };
+    let position = format!("Page {page} of {count}");
+
+    view! {
+        PostList(posts=posts)
+        nav(class="max-w-prose mx-auto mb-10 flex flex-row justify-between gap-4 font-mono text-sm text-red-200", aria-label="Pages") {
+            (newer)
+            div(class="text-gray-400") {
+                (position) " · "
+                a(class="hover:underline", href="/archive") { "archive" }
+            }
+            (older)
+        }
+    }
+}
+
+/// A list of posts, displayed in the order given.
+#[component(inline_props)]
+pub fn PostList(posts: Vec<PostSummary>) -> View {
     view! {
         ul(class="max-w-prose mx-auto") {
             Indexed(
-                list=posts.into_iter().rev().collect::<Vec<_>>(),
+                list=posts,
                 view=|post| {
+                    let published = post.published;
+                    let date = crate::components::date_line(post.date, Some(post.length));
                     view! {
                         li(class="mb-10") {
-                            crate::components::ShowDate(date=post.date)
+                            (if published {
+                                view! {}
+                            } else {
+                                view! {
+                                    crate::components::DraftBadge()
+                                }
+                            })
+                            (date)
                             h1 {
-                                a(class="hover:underline", href=format!("/post/{}", post.filename)) {
+                                a(class="hover:underline", href=format!("/post/{}", post.id)) {
                                     (post.title.clone())
                                 }
                             }
                             p { (post.desc.clone()) }
-                            div(class="text-xs text-gray-400 font-mono") {
+                            div(class="flex flex-row flex-wrap gap-2 text-xs text-gray-400 font-mono") {
                                 Indexed(
                                     list=post.tags,
                                     view=|tag| {
+                                        let href = format!("/tags/{tag}");
                                         view! {
-                                            span { "#" (tag) }
+                                            a(class="hover:underline", href=href) { "#" (tag) }
                                         }
                                     }
                                 )
@@ -1,3 +1,7 @@
 pub mod about;
+pub mod archive;
 pub mod home;
 pub mod post;
+pub mod search;
+pub mod series;
+pub mod tags;
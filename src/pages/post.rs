@@ -1,14 +1,19 @@
+#[cfg_ssr]
 use std::collections::HashMap;
+use std::fmt;
 use std::str::FromStr;
+#[cfg_ssr]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg_ssr]
 use std::sync::LazyLock;
 
-use include_dir::{include_dir, Dir};
-use mdsycx::{ComponentMap, ParseRes};
-use serde::Deserialize;
+use mdsycx::{BodyRes, ComponentMap};
+use serde::{Deserialize, Serialize};
 use sycamore::prelude::*;
-use wasm_bindgen::prelude::*;
+#[cfg_not_ssr]
+use sycamore::web::Suspense;
 
-use crate::shell::set_title;
+use crate::shell::{set_article, set_description, set_image, set_title};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PostDate {
@@ -28,29 +33,97 @@ impl Ord for PostDate {
     }
 }
 
+impl PostDate {
+    /// Returns the number of days in the given month, taking leap years into account.
+    fn days_in_month(year: u32, month: u32) -> u32 {
+        match month {
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Returns the current date in UTC.
+    pub fn today() -> Self {
+        is_ssr! {
+            use std::time::{SystemTime, UNIX_EPOCH};
+
+            let days = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs() / 86400);
+
+            // Convert days since the epoch into a civil date. See
+            // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+            let z = days + 719468;
+            let era = z / 146097;
+            let doe = z - era * 146097;
+            let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+            let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+            let mp = (5 * doy + 2) / 153;
+            let day = doy - (153 * mp + 2) / 5 + 1;
+            let month = if mp < 10 { mp + 3 } else { mp - 9 };
+            let year = yoe + era * 400 + u64::from(month <= 2);
+            PostDate {
+                day: day as u32,
+                month: month as u32,
+                year: year as u32,
+            }
+        }
+        is_not_ssr! {
+            let now = js_sys::Date::new_0();
+            PostDate {
+                day: now.get_utc_date(),
+                month: now.get_utc_month() + 1,
+                year: now.get_utc_full_year(),
+            }
+        }
+    }
+}
+
 impl FromStr for PostDate {
     type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split('-').map(|x| x.parse().unwrap());
-        Ok(PostDate {
-            year: parts.next().ok_or("could not parse year")?,
-            month: parts.next().ok_or("could not parse month")?,
-            day: parts.next().ok_or("could not parse date")?,
-        })
+        let mut parts = s.split('-');
+        let mut next = |err| parts.next().and_then(|x| x.parse::<u32>().ok()).ok_or(err);
+        let year = next("could not parse year")?;
+        let month = next("could not parse month")?;
+        let day = next("could not parse day")?;
+        if parts.next().is_some() {
+            return Err("date should be in format YYYY-MM-DD");
+        }
+
+        if !(1..=12).contains(&month) {
+            return Err("month should be between 1 and 12");
+        }
+        if day == 0 || day > Self::days_in_month(year, month) {
+            return Err("day is out of range for month");
+        }
+        Ok(PostDate { day, month, year })
+    }
+}
+
+impl fmt::Display for PostDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Serialize for PostDate {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
 }
 
 /// Deserialize date in format "YYYY-MM-DD"
-fn deserialize_date<'de, D>(deserializer: D) -> Result<PostDate, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    // TODO: error handling
-    Ok(PostDate::from_str(&s).unwrap())
+impl<'de> Deserialize<'de> for PostDate {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        PostDate::from_str(&s).map_err(serde::de::Error::custom)
+    }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum PostLayout {
     #[default]
@@ -58,14 +131,22 @@ pub enum PostLayout {
     Full,
 }
 
+/// A heading in a post.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    /// The unique id of the heading in the post, used for anchor links.
+    pub id: String,
+}
+
 fn _render_math_default() -> bool {
     false
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PostMetadata {
     pub title: String,
-    #[serde(deserialize_with = "deserialize_date")]
     pub date: PostDate,
     #[serde(default)]
     pub desc: String,
@@ -75,56 +156,243 @@ pub struct PostMetadata {
     pub layout: PostLayout,
     #[serde(default = "_render_math_default")]
     pub render_math: bool,
+    /// Drafts are only published in preview mode.
+    #[serde(default)]
+    pub draft: bool,
+    /// The post is only published once this date is reached.
+    #[serde(default)]
+    pub publish_after: Option<PostDate>,
+    /// Image used for link previews instead of the generated card. Either a path on the site or a
+    /// full URL.
+    #[serde(default)]
+    pub cover: Option<String>,
+    /// The name of the series that the post is a part of. This is used in the URL of the series.
+    #[serde(default)]
+    pub series: Option<String>,
+    /// The position of the post in its series. Posts without an order are sorted by date after the
+    /// ones with an order.
+    #[serde(default)]
+    pub series_order: Option<u32>,
+    /// Show a table of contents next to the post. Only used for prose posts.
+    #[serde(default)]
+    pub toc: bool,
+    /// The headings in prose posts. This is computed by rendering the post body and cannot be set
+    /// in the front matter.
+    #[serde(default)]
+    pub headings: Vec<Heading>,
+    /// The number of words in the post, excluding code blocks and math. This is computed from the
+    /// post body, like the fields below.
+    #[serde(default)]
+    pub word_count: usize,
+    /// Estimated reading time in minutes.
+    #[serde(default)]
+    pub reading_time: usize,
+    /// The number of slides and slide segments, for slide posts.
+    #[serde(default)]
+    pub slide_count: usize,
+    #[serde(default)]
+    pub segment_count: usize,
+    /// The ids of the posts published right before and after this one. These are computed from
+    /// all the posts, like [`PostMetadata::related`].
+    #[serde(default)]
+    pub previous: Option<String>,
+    #[serde(default)]
+    pub next: Option<String>,
+    /// The ids of the posts that share the most tags with this one, most related first.
+    #[serde(default)]
+    pub related: Vec<String>,
     /// The filename of the original markdown file. This is not deserialized by serde but populated
     /// manually.
     #[serde(skip)]
     pub filename: String,
 }
 
-static FILES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/posts");
-
-pub static POSTS: LazyLock<HashMap<String, ParseRes<PostMetadata>>> = LazyLock::new(|| {
-    FILES
-        .find("**/*.mdx")
-        .unwrap()
-        .map(|dir| {
-            let file = dir.as_file().unwrap();
-            let contents = file.contents_utf8().expect("file not utf8");
-            let mut parse_res: ParseRes<PostMetadata> =
-                mdsycx::parse(contents).expect("parse failed");
-
-            let filename = file
-                .path()
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string();
-            parse_res.front_matter.filename = filename.clone();
-            (filename, parse_res)
-        })
-        .collect()
+impl PostMetadata {
+    /// Whether the post should be visible on the live site, i.e. it is not a draft and it is not
+    /// scheduled for a later date.
+    pub fn is_published(&self) -> bool {
+        !self.draft
+            && self
+                .publish_after
+                .is_none_or(|date| date <= PostDate::today())
+    }
+
+    /// A short description of the length of the post, e.g. "1200 words, 6 min read". For slide
+    /// posts, this is the number of slides and segments instead.
+    pub fn length_summary(&self) -> String {
+        fn plural(n: usize, word: &str) -> String {
+            if n == 1 {
+                format!("{n} {word}")
+            } else {
+                format!("{n} {word}s")
+            }
+        }
+
+        if self.slide_count > 0 {
+            format!(
+                "{}, {}",
+                plural(self.slide_count, "slide"),
+                plural(self.segment_count, "segment")
+            )
+        } else {
+            format!(
+                "{}, {} min read",
+                plural(self.word_count, "word"),
+                self.reading_time
+            )
+        }
+    }
+
+    /// The summary of the post that is shown when listing it.
+    pub fn summary(&self) -> PostSummary {
+        PostSummary {
+            id: self.filename.clone(),
+            title: self.title.clone(),
+            date: self.date,
+            desc: self.desc.clone(),
+            tags: self.tags.clone(),
+            published: self.is_published(),
+            length: self.length_summary(),
+            series: self.series.clone(),
+            series_order: self.series_order,
+        }
+    }
+}
+
+/// The part of [`PostMetadata`] that is needed to list a post, e.g. on the home page. This is
+/// embedded into every page that lists posts, so it only has what those pages show.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PostSummary {
+    /// The filename of the post, which is used in its URL.
+    pub id: String,
+    pub title: String,
+    pub date: PostDate,
+    pub desc: String,
+    pub tags: Vec<String>,
+    /// See [`PostMetadata::is_published`]. This is decided when the site is built so that the
+    /// client shows the same posts as drafts.
+    pub published: bool,
+    /// See [`PostMetadata::length_summary`].
+    pub length: String,
+    pub series: Option<String>,
+    pub series_order: Option<u32>,
+}
+
+/// Set by [`include_drafts`].
+#[cfg_ssr]
+static INCLUDE_DRAFTS: AtomicBool = AtomicBool::new(false);
+
+/// Include unpublished posts in the site, e.g. for the `--drafts` flag. This needs to be called
+/// before the posts are first used.
+#[cfg_ssr]
+pub fn include_drafts() {
+    INCLUDE_DRAFTS.store(true, Ordering::Relaxed);
+}
+
+/// Whether unpublished posts should be included in the site. This is enabled by the `preview`
+/// feature, by setting the `BLOG_PREVIEW` environment variable at compile time or by calling
+/// [`include_drafts`].
+#[cfg_ssr]
+pub fn preview_enabled() -> bool {
+    cfg!(feature = "preview")
+        || option_env!("BLOG_PREVIEW").is_some()
+        || INCLUDE_DRAFTS.load(Ordering::Relaxed)
+}
+
+/// Metadata of all the posts, indexed by filename. This is only available during SSR. The client
+/// gets the summaries of the posts from [`posts_by_date`] and the metadata of a post from its page.
+#[cfg_ssr]
+pub static POST_INDEX: LazyLock<HashMap<String, PostMetadata>> = LazyLock::new(|| {
+    let mut index: HashMap<_, _> = crate::posts::POSTS
+        .iter()
+        .map(|(id, post)| (id.clone(), post.front_matter.clone()))
+        .collect();
+    crate::posts::link_posts(&mut index);
+    index
 });
 
-#[component(inline_props)]
-pub fn PostView(id: String) -> View {
-    let Some(post) = POSTS.get(&id) else {
-        return view! {
-            crate::shell::NotFound()
-        };
-    };
+/// Summaries of all the posts, see [`posts_by_date`].
+#[cfg_ssr]
+static POST_SUMMARIES: LazyLock<Vec<PostSummary>> = LazyLock::new(|| {
+    let mut posts = POST_INDEX
+        .values()
+        .map(PostMetadata::summary)
+        .collect::<Vec<_>>();
+    posts.sort_by(|a, b| (b.date, &b.id).cmp(&(a.date, &a.id)));
+    posts
+});
+
+/// Summaries of all the posts, once they have been loaded by [`WithPostIndex`].
+#[cfg_not_ssr]
+static POST_SUMMARIES: std::sync::OnceLock<Vec<PostSummary>> = std::sync::OnceLock::new();
 
-    // Code highlighting.
-    on_mount(highlightAll);
+/// Id of the element in which the summaries of the posts are embedded.
+pub static POST_INDEX_ID: &str = "post-index";
 
-    // TODO: Only import MathJax if needed.
-    if post.front_matter.render_math {
-        on_mount(move || MathJax().typeset());
+/// Path of the summaries of the posts on the site, for pages that were not rendered with them.
+pub static POST_INDEX_PATH: &str = "/posts.json";
+
+/// Summaries of all the posts, sorted by date descending. Posts on the same date are sorted by
+/// filename so that the order is the same on the server and the client.
+///
+/// On the client, this is empty outside of [`WithPostIndex`].
+pub fn posts_by_date() -> Vec<PostSummary> {
+    is_ssr! {
+        POST_SUMMARIES.clone()
+    }
+    is_not_ssr! {
+        POST_SUMMARIES.get().cloned().unwrap_or_default()
+    }
+}
+
+/// Render a page that lists posts once the summaries of the posts are available.
+///
+/// The summaries are embedded into the page during SSR so that the client can hydrate without an
+/// extra request. They are only fetched when navigating to the page from one that does not list
+/// posts.
+#[component(inline_props)]
+pub fn WithPostIndex(route: crate::Routes, view: fn(crate::Routes) -> View) -> View {
+    is_ssr! {
+        crate::shell::embed_json(POST_INDEX_ID, crate::posts::post_index_json());
+        view(route)
+    }
+    is_not_ssr! {
+        if POST_SUMMARIES.get().is_none() {
+            let embedded = crate::shell::read_embedded(POST_INDEX_ID)
+                .and_then(|json| serde_json::from_str(&json).ok());
+            if let Some(posts) = embedded {
+                let _ = POST_SUMMARIES.set(posts);
+            }
+        }
+        if POST_SUMMARIES.get().is_some() {
+            return view(route);
+        }
+        view! {
+            Suspense(fallback=|| "Loading...".into()) {
+                FetchedPostIndex(route=route, view=view)
+            }
+        }
     }
+}
 
-    set_title(format!("{} - lukechu", post.front_matter.title));
+/// Fetch the summaries of the posts from the server and render the page.
+#[cfg_not_ssr]
+#[component(inline_props)]
+async fn FetchedPostIndex(route: crate::Routes, view: fn(crate::Routes) -> View) -> View {
+    match crate::fetch::fetch_json::<Vec<PostSummary>>(POST_INDEX_PATH).await {
+        Ok(posts) => {
+            let _ = POST_SUMMARIES.set(posts);
+            view(route)
+        }
+        Err(err) => view! {
+            p { "Could not load posts: " (err.to_string()) }
+        },
+    }
+}
 
-    let components = ComponentMap::new()
+/// The components that can be used inside a post.
+pub fn post_components() -> ComponentMap {
+    ComponentMap::new()
         .with("SlideShow", crate::components::slides::SlideShow)
         .with("Slide", crate::components::slides::Slide)
         .with("SlideSegment", crate::components::slides::SlideSegment)
@@ -132,59 +400,255 @@ pub fn PostView(id: String) -> View {
             "NextSegmentLink",
             crate::components::slides::NextSegmentLink,
         )
+        .with("Notes", crate::components::slides::Notes)
         .with("span", crate::components::math::MathDisplay)
-        .with("ShowDate", crate::components::ShowDate);
-
-    // FIXME: issue with upstream sycamore where portal children try to hydrate but cannot
-    // since they are not present in the SSR Html.
-    let portal = view! {
-        // Portal(selector="head") {
-        //     (if !post.front_matter.desc.is_empty() {
-        //         view! {
-        //             meta(name="description", content=post.front_matter.desc.clone())
-        //         }
-        //     } else {
-        //         view! {}
-        //     })
-        // }
-    };
+        .with("code", crate::components::code::Code)
+        .with("ShowDate", crate::components::ShowDate)
+        .with("h1", crate::components::toc::H1)
+        .with("h2", crate::components::toc::H2)
+        .with("h3", crate::components::toc::H3)
+        .with("h4", crate::components::toc::H4)
+        .with("h5", crate::components::toc::H5)
+        .with("h6", crate::components::toc::H6)
+}
 
-    match post.front_matter.layout {
-        PostLayout::Prose => view! {
-            div(class="post-content max-w-prose mx-auto") {
-                crate::components::ShowDate(date=post.front_matter.date)
+/// Everything that the page of a post shows. This is embedded into the page during SSR and written
+/// to `/post/<id>.json`, which is fetched by the client when navigating to the post.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostPage {
+    pub post: PostMetadata,
+    /// The posts that the page links to: the previous and next posts, the related posts and the
+    /// parts of the series that the post is in, including itself.
+    pub linked: Vec<PostSummary>,
+    pub body: BodyRes,
+    /// The server fragments rendered inside the body. These are only needed when the page is
+    /// fetched, since they are already in the page when hydrating.
+    #[serde(default)]
+    pub fragments: Vec<String>,
+}
+
+/// Id of the element in which the [`PostPage`] of the post is embedded.
+fn post_page_id(id: &str) -> String {
+    format!("post-page-{id}")
+}
+
+/// Read the page of the post that was embedded during SSR. This returns `None` if we are not
+/// hydrating, e.g. after navigating from another page, in which case the page needs to be fetched.
+#[cfg_not_ssr]
+fn embedded_page(id: &str) -> Option<PostPage> {
+    if !sycamore::web::is_hydrating() {
+        return None;
+    }
+    let json = crate::shell::read_embedded(&post_page_id(id))?;
+    let mut page: PostPage = serde_json::from_str(&json).ok()?;
+    page.post.filename = id.to_string();
+    Some(page)
+}
 
-                mdsycx::MDSycX(body=post.body.clone(), components=components)
+/// Fetch the page of the post from the server and render it.
+#[cfg_not_ssr]
+#[component(inline_props)]
+async fn FetchedPost(id: String) -> View {
+    let url = format!("/post/{id}.json");
+    match crate::fetch::fetch_json::<PostPage>(&url).await {
+        Ok(mut page) => {
+            page.post.filename = id;
+            crate::server_component::replay_fragments(std::mem::take(&mut page.fragments));
+            view! {
+                PostContent(page=page)
             }
-            (portal)
+        }
+        Err(crate::fetch::FetchError::Status { status: 404, .. }) => view! {
+            crate::shell::NotFound()
         },
-        PostLayout::Full => view! {
-            div(class="post-content") {
-                mdsycx::MDSycX(body=post.body.clone(), components=components)
-            }
-            (portal)
+        Err(err) => view! {
+            p { "Could not load post: " (err.to_string()) }
         },
     }
 }
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = Prism)]
-    fn highlightAll();
-}
+/// Links to the previous and next posts and to related posts, shown after the post.
+#[component(inline_props)]
+fn PostFooter(post: PostMetadata, linked: Vec<PostSummary>) -> View {
+    let find = |id: &String| linked.iter().find(|other| &other.id == id).cloned();
+    let link = |id: Option<String>, older: bool| {
+        let Some(other) = id.as_ref().and_then(find) else {
+            return view! {
+                span {}
+            };
+        };
+        let href = format!("/post/{}", other.id);
+        let text = if older {
+            format!("← {}", other.title)
+        } else {
+            format!("{} →", other.title)
+        };
+        view! {
+            a(class="hover:underline", href=href) { (text) }
+        }
+    };
+    let previous = link(post.previous, true);
+    let next = link(post.next, false);
+
+    let related = post.related.iter().filter_map(find).collect::<Vec<_>>();
+    let related = if related.is_empty() {
+        view! {}
+    } else {
+        view! {
+            h2(class="text-base text-red-200 mt-6 mb-2") { "Related posts" }
+            ul(class="flex flex-col gap-1") {
+                Indexed(
+                    list=related,
+                    view=|post| {
+                        let href = format!("/post/{}", post.id);
+                        let title = post.title;
+                        let date = post.date.to_string();
+                        view! {
+                            li {
+                                a(class="hover:underline", href=href) { (title) }
+                                span(class="text-gray-400") { " · " (date) }
+                            }
+                        }
+                    },
+                )
+            }
+        }
+    };
 
-#[wasm_bindgen]
-extern "C" {
-    type MathJax;
+    view! {
+        footer(class="max-w-prose mx-auto mt-10 pt-4 border-t border-slate-700 font-mono text-sm") {
+            div(class="flex flex-row justify-between gap-4") {
+                (previous)
+                (next)
+            }
+            (related)
+        }
+    }
+}
 
-    #[wasm_bindgen(method)]
-    fn typeset(this: &MathJax);
+#[component(inline_props)]
+pub fn PostView(id: String) -> View {
+    is_ssr! {
+        let Some(page) = crate::posts::post_page(&id) else {
+            return view! {
+                crate::shell::NotFound()
+            };
+        };
+        let json = serde_json::to_string(&page).expect("failed to serialize post page");
+        crate::shell::embed_json(post_page_id(&id), json);
+        view! {
+            PostContent(page=page)
+        }
+    }
+    is_not_ssr! {
+        match embedded_page(&id) {
+            Some(page) => view! {
+                PostContent(page=page)
+            },
+            None => view! {
+                Suspense(fallback=|| "Loading...".into()) {
+                    FetchedPost(id=id)
+                }
+            },
+        }
+    }
 }
 
-#[allow(non_snake_case)]
-fn MathJax() -> MathJax {
-    window()
-        .get("MathJax")
-        .expect("could not get MathJax")
-        .unchecked_into()
+/// The post along with the posts that it links to.
+#[component(inline_props)]
+fn PostContent(page: PostPage) -> View {
+    let PostPage {
+        post, linked, body, ..
+    } = page;
+    let id = post.filename.clone();
+
+    set_title(format!("{} - {}", post.title, crate::config::site().title));
+    if !post.desc.is_empty() {
+        set_description(post.desc.clone());
+    }
+    set_article(post.date, post.tags.clone());
+    set_image(
+        post.cover
+            .clone()
+            .unwrap_or_else(|| format!("/post/{id}.png")),
+    );
+
+    is_ssr! {
+        crate::server_component::record_fragments(format!("post/{id}"));
+    }
+
+    provide_context(crate::components::math::RenderMath(post.render_math));
+    // Slide posts show the date on their first slide, so their length is shown on its own in the
+    // header instead.
+    let length = post.length_summary();
+    if post.layout == PostLayout::Prose {
+        provide_context(crate::components::PostLength(length.clone()));
+        crate::components::toc::provide_heading_ids(&post.headings);
+    }
+
+    let body = view! {
+        mdsycx::MDSycX(body=body, components=post_components())
+    };
+
+    let draft = if post.is_published() {
+        view! {}
+    } else {
+        view! {
+            crate::components::DraftBadge()
+        }
+    };
+
+    let series_top = view! {
+        crate::pages::series::SeriesNav(post=post.summary(), posts=linked.clone())
+    };
+    let series_bottom = view! {
+        crate::pages::series::SeriesNav(post=post.summary(), posts=linked.clone())
+    };
+    let footer = view! {
+        PostFooter(post=post.clone(), linked=linked)
+    };
+
+    match post.layout {
+        PostLayout::Prose if post.toc => view! {
+            div(class="xl:grid xl:grid-cols-[1fr_minmax(0,65ch)_1fr] xl:gap-8") {
+                div(class="post-content max-w-prose mx-auto xl:col-start-2") {
+                    (draft)
+                    crate::components::ShowDate(date=post.date)
+                    (series_top)
+
+                    (body)
+                    (series_bottom)
+                }
+                aside(class="hidden xl:block") {
+                    crate::components::toc::TableOfContents(headings=post.headings)
+                }
+            }
+            (footer)
+        },
+        PostLayout::Prose => view! {
+            div(class="post-content max-w-prose mx-auto") {
+                (draft)
+                crate::components::ShowDate(date=post.date)
+                (series_top)
+
+                (body)
+                (series_bottom)
+            }
+            (footer)
+        },
+        PostLayout::Full => view! {
+            div(class="fixed top-12 right-3 z-50") {
+                (draft)
+            }
+            div(class="post-content") {
+                div(class="max-w-prose mx-auto") {
+                    p(class="text-sm text-gray-400 !mb-0 font-mono") { (length) }
+                    (series_top)
+                }
+                (body)
+                div(class="max-w-prose mx-auto") { (series_bottom) }
+            }
+            (footer)
+        },
+    }
 }
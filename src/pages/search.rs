@@ -0,0 +1,130 @@
+use sycamore::prelude::*;
+
+use crate::{
+    config::site,
+    search::SearchIndex,
+    shell::{set_description, set_title},
+};
+
+/// The search index, kept around so that it only needs to be fetched once.
+#[cfg_not_ssr]
+static INDEX: std::sync::OnceLock<SearchIndex> = std::sync::OnceLock::new();
+
+#[cfg_not_ssr]
+async fn load_index() -> Result<&'static SearchIndex, crate::fetch::FetchError> {
+    if let Some(index) = INDEX.get() {
+        return Ok(index);
+    }
+    let index = crate::fetch::fetch_json(crate::search::SEARCH_INDEX_PATH).await?;
+    Ok(INDEX.get_or_init(|| index))
+}
+
+/// Read the query from the `q` parameter of the URL, which is set by the search box in the header.
+#[cfg_not_ssr]
+fn query_from_url() -> Option<String> {
+    let search = window().location().search().ok()?;
+    web_sys::UrlSearchParams::new_with_str(&search)
+        .ok()?
+        .get("q")
+}
+
+#[component]
+pub fn Search() -> View {
+    set_title(format!("Search - {}", site().title));
+    set_description("Search the posts on this site.");
+
+    let query = create_signal(String::new());
+    // The search index is only loaded once the search page is shown. This is `None` while it is
+    // loading.
+    let index = create_signal(None::<Result<&'static SearchIndex, String>>);
+
+    let load = move || {
+        is_not_ssr! {
+            sycamore::futures::spawn_local_scoped(async move {
+                index.set(Some(load_index().await.map_err(|err| err.to_string())));
+            });
+        }
+    };
+    // Only update the query after hydrating so that the page matches the one rendered on the
+    // server, which does not know the query.
+    on_mount(move || {
+        is_not_ssr! {
+            if let Some(q) = query_from_url() {
+                query.set(q);
+            }
+        }
+        load();
+    });
+
+    let results = move || {
+        let q = query.get_clone();
+        if q.trim().is_empty() {
+            return view! {};
+        }
+        match index.get_clone() {
+            None => view! {
+                p(class="text-gray-400") { "Loading..." }
+            },
+            Some(Err(err)) => view! {
+                crate::fetch::FetchErrorView(what="search index", error=err, on_retry=move || {
+                    index.set(None);
+                    load();
+                })
+            },
+            Some(Ok(index)) => {
+                let results = index.search(&q);
+                if results.is_empty() {
+                    return view! {
+                        p(class="text-gray-400") { "No results." }
+                    };
+                }
+                let results = results
+                    .into_iter()
+                    .map(|result| {
+                        let href = format!("/post/{}", result.id);
+                        let date = result.date.to_string();
+                        let snippet = result
+                            .snippet
+                            .into_iter()
+                            .map(|(text, highlight)| {
+                                if highlight {
+                                    view! {
+                                        mark(class="bg-red-900 text-red-100 rounded-sm") { (text) }
+                                    }
+                                } else {
+                                    text.into()
+                                }
+                            })
+                            .collect::<Vec<View>>();
+                        let title = result.title;
+                        view! {
+                            li(class="mb-6") {
+                                a(class="text-lg hover:underline text-red-200", href=href) { (title) }
+                                p(class="text-xs text-gray-400 font-mono !mb-1") { (date) }
+                                p(class="text-sm") { (snippet) }
+                            }
+                        }
+                    })
+                    .collect::<Vec<View>>();
+                view! {
+                    ul { (results) }
+                }
+            }
+        }
+    };
+
+    view! {
+        div(class="post-content max-w-prose mx-auto") {
+            h1 { "Search" }
+            input(
+                r#type="search",
+                class="w-full mb-6 px-2 py-1 rounded bg-slate-900 border border-slate-700 font-mono text-sm",
+                placeholder="Search posts",
+                aria-label="Search posts",
+                autofocus=true,
+                bind:value=query,
+            )
+            (results)
+        }
+    }
+}
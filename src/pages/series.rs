@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+
+use sycamore::prelude::*;
+
+use crate::{
+    config::site,
+    pages::{
+        home::PostList,
+        post::{posts_by_date, PostSummary},
+    },
+    shell::{set_description, set_title},
+};
+
+/// The posts in the series, in order. Parts with a `series_order` come first, followed by the rest
+/// in the order that they were published.
+pub fn series_parts(name: &str) -> Vec<PostSummary> {
+    parts_of(posts_by_date(), name)
+}
+
+/// The posts in `posts` that are in the series, in the same order as [`series_parts`].
+pub fn parts_of(posts: Vec<PostSummary>, name: &str) -> Vec<PostSummary> {
+    let mut parts = posts
+        .into_iter()
+        .filter(|post| post.series.as_deref() == Some(name))
+        .collect::<Vec<_>>();
+    parts.sort_by_key(|post| (post.series_order.is_none(), post.series_order, post.date));
+    parts
+}
+
+/// The names of all the series, sorted by name.
+pub fn all_series() -> Vec<String> {
+    posts_by_date()
+        .into_iter()
+        .filter_map(|post| post.series)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// A list of all the parts of a series.
+#[component(inline_props)]
+pub fn SeriesView(name: String) -> View {
+    let parts = series_parts(&name);
+    if parts.is_empty() {
+        return view! {
+            crate::shell::NotFound()
+        };
+    }
+
+    set_title(format!("{name} - {}", site().title));
+    set_description(format!("All the parts of the series {name}."));
+    let count = match parts.len() {
+        1 => "1 part".to_string(),
+        n => format!("{n} parts"),
+    };
+    view! {
+        div(class="max-w-prose mx-auto mb-10 font-mono") {
+            h1(class="text-2xl") { "Series: " (name) }
+            div(class="text-xs text-gray-400") { (count) }
+        }
+        PostList(posts=parts)
+    }
+}
+
+/// Shows which part of its series the post is, along with links to the previous and next parts.
+/// The parts are looked up in `posts`. Nothing is rendered if the post is not in a series.
+#[component(inline_props)]
+pub fn SeriesNav(post: PostSummary, posts: Vec<PostSummary>) -> View {
+    let Some(name) = post.series else {
+        return view! {};
+    };
+    let parts = parts_of(posts, &name);
+    let Some(index) = parts.iter().position(|part| part.id == post.id) else {
+        return view! {};
+    };
+
+    let position = format!("Part {} of {} in ", index + 1, parts.len());
+    let series_href = format!("/series/{name}");
+    let link = |part: Option<&PostSummary>, previous: bool| match part {
+        Some(part) => {
+            let href = format!("/post/{}", part.id);
+            let text = if previous {
+                format!("← {}", part.title)
+            } else {
+                format!("{} →", part.title)
+            };
+            view! {
+                a(class="hover:underline", href=href) { (text) }
+            }
+        }
+        None => view! {
+            span {}
+        },
+    };
+    let previous = link(index.checked_sub(1).and_then(|i| parts.get(i)), true);
+    let next = link(parts.get(index + 1), false);
+
+    view! {
+        nav(class="border-l-4 border-red-300 bg-slate-900 p-2 my-4 rounded font-mono text-sm", aria-label="Series") {
+            p(class="!mb-2") {
+                (position)
+                a(class="hover:underline", href=series_href) { (name) }
+            }
+            div(class="flex flex-row justify-between gap-4") {
+                (previous)
+                (next)
+            }
+        }
+    }
+}
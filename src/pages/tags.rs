@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use sycamore::prelude::*;
+
+use crate::{
+    config::site,
+    pages::{home::PostList, post::posts_by_date},
+    shell::{set_description, set_title},
+};
+
+/// All the tags along with the number of posts with each tag, sorted by tag name.
+pub fn tag_counts() -> Vec<(String, usize)> {
+    let mut counts = BTreeMap::<String, usize>::new();
+    for post in posts_by_date() {
+        for tag in post.tags {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+    counts.into_iter().collect()
+}
+
+#[component]
+pub fn Tags() -> View {
+    set_title(format!("Tags - {}", site().title));
+    view! {
+        div(class="post-content max-w-prose mx-auto") {
+            h1 { "Tags" }
+            ul(class="font-mono") {
+                Indexed(
+                    list=tag_counts(),
+                    view=|(tag, count)| {
+                        let href = format!("/tags/{tag}");
+                        let count = match count {
+                            1 => " (1 post)".to_string(),
+                            n => format!(" ({n} posts)"),
+                        };
+                        view! {
+                            li {
+                                a(class="hover:underline", href=href) { "#" (tag) }
+                                span(class="text-gray-400") { (count) }
+                            }
+                        }
+                    }
+                )
+            }
+        }
+    }
+}
+
+/// A list of all the posts with a certain tag.
+#[component(inline_props)]
+pub fn TagView(tag: String) -> View {
+    let posts = posts_by_date()
+        .into_iter()
+        .filter(|post| post.tags.contains(&tag))
+        .collect::<Vec<_>>();
+    if posts.is_empty() {
+        return view! {
+            crate::shell::NotFound()
+        };
+    }
+
+    set_title(format!("#{tag} - {}", site().title));
+    set_description(format!("Posts tagged #{tag}."));
+    let feed = format!("/tags/{tag}.xml");
+    view! {
+        div(class="max-w-prose mx-auto mb-10 font-mono") {
+            h1(class="text-2xl") { "#" (tag) }
+            div(class="text-xs text-gray-400") {
+                a(class="hover:underline", href="/tags") { "all tags" }
+                " · "
+                a(class="hover:underline", href=feed, rel="external") { "feed" }
+            }
+        }
+        PostList(posts=posts)
+    }
+}
@@ -0,0 +1,589 @@
+//! Loading and validating the post sources. This is only used during SSR so that the posts do not
+//! need to be included in the client binary.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use include_dir::{include_dir, Dir, File};
+use mdsycx::ParseRes;
+use sycamore::prelude::*;
+
+use crate::components::slides::{manifest_base, ManimSlides};
+use crate::pages::post::{
+    post_components, posts_by_date, preview_enabled, PostDate, PostLayout, PostMetadata, PostPage,
+    POST_INDEX,
+};
+
+/// Keys that are allowed in the front matter of a post.
+static FRONT_MATTER_KEYS: &[&str] = &[
+    "title",
+    "date",
+    "desc",
+    "tags",
+    "layout",
+    "render_math",
+    "draft",
+    "publish_after",
+    "cover",
+    "toc",
+    "series",
+    "series_order",
+];
+
+/// An error found while parsing a post.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostError {
+    /// Path of the post, relative to the crate root.
+    pub path: String,
+    /// The line in the file where the error occurred, if known.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for PostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.path, self.message),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+/// Split the front matter out of the post. The front matter starts on the second line of the file,
+/// right after the opening `---`.
+fn split_front_matter(contents: &str) -> Option<&str> {
+    let rest = contents
+        .strip_prefix("---\n")
+        .or_else(|| contents.strip_prefix("---\r\n"))?;
+    let end = rest
+        .match_indices("---")
+        .find(|&(i, _)| i == 0 || rest.as_bytes()[i - 1] == b'\n')?
+        .0;
+    Some(&rest[..end])
+}
+
+/// Find the line in the file on which `key` is defined in the front matter.
+fn find_key_line(front_matter: &str, key: &str) -> Option<usize> {
+    front_matter
+        .lines()
+        .position(|line| {
+            line.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+        .map(|i| i + 2)
+}
+
+/// The markdown body of the post, after the front matter.
+fn split_body(contents: &str) -> &str {
+    let Some(front_matter) = split_front_matter(contents) else {
+        return contents;
+    };
+    // The front matter is a subslice of the contents so we can find where it ends.
+    let end = front_matter.as_ptr() as usize - contents.as_ptr() as usize + front_matter.len();
+    let rest = &contents[end..];
+    rest.split_once('\n').map_or("", |(_, body)| body)
+}
+
+/// Lines of the markdown body that are not inside fenced code blocks.
+fn prose_lines(body: &str) -> impl Iterator<Item = &str> {
+    let mut in_code = false;
+    body.lines().filter(move |line| {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            return false;
+        }
+        !in_code
+    })
+}
+
+/// Average reading speed used to estimate the reading time of a post, in words per minute.
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Remove inline math, tags, link targets and emphasis from a line of markdown so that only the
+/// words that are read remain.
+fn strip_non_prose(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        let end = match c {
+            '*' | '`' | '[' => continue,
+            '$' => '$',
+            '<' => '>',
+            ']' if rest.starts_with('(') => ')',
+            _ => {
+                stripped.push(c);
+                continue;
+            }
+        };
+        rest = rest.split_once(end).map_or("", |(_, rest)| rest);
+        stripped.push(' ');
+    }
+    stripped
+}
+
+/// The text in the markdown body that is read, without code blocks, math and markdown syntax. Each
+/// line of the body is on its own line.
+fn prose_text(body: &str) -> String {
+    let mut in_math = false;
+    let mut text = String::new();
+    for line in prose_lines(body) {
+        // Display math can also be inside block quotes.
+        let line = line.trim_start_matches(|c: char| c == '>' || c.is_whitespace());
+        if let Some(rest) = line.strip_prefix("$$") {
+            // Math on a single line, e.g. `$$ x^2 $$`, does not start a block.
+            if !rest.trim_end().ends_with("$$") {
+                in_math = !in_math;
+            }
+            continue;
+        }
+        if in_math {
+            continue;
+        }
+        // Remove heading and list markers.
+        let line = line.trim_start_matches(['#', '-', '+', ' ']);
+        text.push_str(strip_non_prose(line).trim());
+        text.push('\n');
+    }
+    text
+}
+
+/// Count the words in the markdown body, excluding code blocks and math.
+fn count_words(body: &str) -> usize {
+    prose_text(body)
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Count the occurrences of the component `tag` in the markdown body.
+fn count_components(body: &str, tag: &str) -> usize {
+    let open = format!("<{tag}");
+    prose_lines(body)
+        .map(|line| {
+            line.match_indices(&open)
+                .filter(|(i, _)| {
+                    let rest = &line[i + open.len()..];
+                    rest.is_empty()
+                        || rest.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                })
+                .count()
+        })
+        .sum()
+}
+
+/// Parse a single post, collecting every error that is found instead of stopping at the first
+/// one.
+fn parse_post(file: &File<'_>) -> Result<(String, ParseRes<PostMetadata>), Vec<PostError>> {
+    let path = format!("posts/{}", file.path().display());
+    let error = |line, message| PostError {
+        path: path.clone(),
+        line,
+        message,
+    };
+
+    let Some(contents) = file.contents_utf8() else {
+        return Err(vec![error(None, "file is not valid UTF-8".to_string())]);
+    };
+    let Some(front_matter) = split_front_matter(contents) else {
+        return Err(vec![error(Some(1), "missing front matter".to_string())]);
+    };
+
+    // Line numbers reported by serde_yaml are relative to the start of the front matter. Errors
+    // without a position (such as missing fields) are reported at the very start.
+    let yaml_error = |err: serde_yaml::Error| {
+        let message = err.to_string();
+        let message = message
+            .split_once(" at line ")
+            .map_or(message.as_str(), |(message, _)| message);
+        let line = err
+            .location()
+            .filter(|location| (location.line(), location.column()) != (1, 1))
+            .map(|location| location.line() + 1);
+        error(line, message.to_string())
+    };
+
+    let mapping = match serde_yaml::from_str::<serde_yaml::Mapping>(front_matter) {
+        Ok(mapping) => mapping,
+        Err(err) => return Err(vec![yaml_error(err)]),
+    };
+
+    let mut errors = Vec::new();
+    for key in mapping.keys() {
+        match key.as_str() {
+            Some(key) if FRONT_MATTER_KEYS.contains(&key) => {}
+            Some(key) => errors.push(error(
+                find_key_line(front_matter, key),
+                format!("unknown key `{key}` in front matter"),
+            )),
+            None => errors.push(error(None, format!("invalid key {key:?} in front matter"))),
+        }
+    }
+
+    // Check the fields that commonly contain typos by hand so that they can all be reported at
+    // once with an accurate line number.
+    for key in ["date", "publish_after"] {
+        if let Some(date) = mapping.get(key) {
+            if let Err(err) = date
+                .as_str()
+                .ok_or("expected a string")
+                .and_then(PostDate::from_str)
+            {
+                errors.push(error(
+                    find_key_line(front_matter, key),
+                    format!("invalid date for `{key}`: {err}"),
+                ));
+            }
+        }
+    }
+    if let Some(layout) = mapping.get("layout") {
+        let message = match layout.as_str() {
+            Some("prose" | "full") => None,
+            Some(layout) => Some(format!(
+                "unknown layout `{layout}`, expected `prose` or `full`"
+            )),
+            None => Some("layout should be a string".to_string()),
+        };
+        if let Some(message) = message {
+            errors.push(error(find_key_line(front_matter, "layout"), message));
+        }
+    }
+
+    // Anything else is caught when deserializing the front matter.
+    if errors.is_empty() {
+        if let Err(err) = serde_yaml::from_str::<PostMetadata>(front_matter) {
+            errors.push(yaml_error(err));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut parse_res: ParseRes<PostMetadata> = match mdsycx::parse(contents) {
+        Ok(parse_res) => parse_res,
+        Err(err) => return Err(vec![error(None, format!("could not parse post: {err:?}"))]),
+    };
+
+    let filename = file
+        .path()
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| vec![error(None, "invalid file name".to_string())])?
+        .to_string();
+
+    // Fill in the fields that are computed from the post.
+    let body = split_body(contents);
+    let meta = &mut parse_res.front_matter;
+    meta.filename = filename.clone();
+    if meta.layout == PostLayout::Prose {
+        let body = parse_res.body.clone();
+        let render_math = meta.render_math;
+        meta.headings = crate::components::toc::collect_headings(move || {
+            provide_context(crate::components::math::RenderMath(render_math));
+            view! {
+                mdsycx::MDSycX(body=body, components=post_components())
+            }
+        });
+    }
+    meta.word_count = count_words(body);
+    meta.reading_time = meta.word_count.div_ceil(WORDS_PER_MINUTE).max(1);
+    meta.slide_count = count_components(body, "Slide");
+    meta.segment_count = count_components(body, "SlideSegment");
+    Ok((filename, parse_res))
+}
+
+/// Slide videos that are known to be missing from the repository. These are reported as warnings
+/// instead of errors until they are committed. Remove entries from this list once the files exist.
+static KNOWN_MISSING_MEDIA: &[&str] = &[
+    "/assets/low-energy-transfers/slides/files/LeoToMoon/ca9b7ec7604e9d726df7e5b8bf02e3e5b47999fd3e8ebbe21f8fdc06e8aada0b.mp4",
+    "/assets/low-energy-transfers/slides/files/Manifolds3Body/c69bd3de60795c9080bd89592752693eb54d562b2dd25f7151135692ebc95534.mp4",
+];
+
+/// Check that the slide video manifests referenced by `video_json` props in the post exist, can be
+/// parsed and that all the videos they list exist.
+fn validate_slides(file: &File) -> Vec<PostError> {
+    let path = format!("posts/{}", file.path().display());
+    let Some(contents) = file.contents_utf8() else {
+        // Already reported by `parse_post`.
+        return Vec::new();
+    };
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let mut errors = Vec::new();
+    for (i, _) in contents.match_indices("video_json=\"") {
+        let line = contents[..i].matches('\n').count() + 1;
+        let mut error = |message| {
+            errors.push(PostError {
+                path: path.clone(),
+                line: Some(line),
+                message,
+            })
+        };
+
+        let start = i + "video_json=\"".len();
+        let Some(len) = contents[start..].find('"') else {
+            error("unterminated `video_json` prop".to_string());
+            continue;
+        };
+        let video_json = &contents[start..start + len];
+
+        let (Some(base), Some(json_path)) =
+            (manifest_base(video_json), video_json.strip_prefix('/'))
+        else {
+            error(format!(
+                "slide manifest `{video_json}` should be an absolute path of the form `/<dir>/slides/<name>.json`"
+            ));
+            continue;
+        };
+        let Ok(json) = std::fs::read_to_string(root.join(json_path)) else {
+            error(format!("slide manifest `{video_json}` does not exist"));
+            continue;
+        };
+        let manifest = match serde_json::from_str::<ManimSlides>(&json) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                error(format!(
+                    "could not parse slide manifest `{video_json}`: {err}"
+                ));
+                continue;
+            }
+        };
+
+        let media = manifest
+            .slides
+            .iter()
+            .flat_map(|slide| std::iter::once(&slide.file).chain(slide.rev_file.as_ref()));
+        for media in media {
+            let url = format!("{base}/{media}");
+            if root.join(url.trim_start_matches('/')).is_file() {
+                continue;
+            }
+            if KNOWN_MISSING_MEDIA.contains(&url.as_str()) {
+                eprintln!("warning: {path}:{line}: slide manifest `{video_json}` references missing file `{url}`");
+            } else {
+                error(format!(
+                    "slide manifest `{video_json}` references missing file `{url}`"
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// Whether the text can be used in a URL and as a file name without escaping, e.g. for tags and
+/// series names.
+pub(crate) fn is_slug(text: &str) -> bool {
+    !text.is_empty()
+        && !text.starts_with('-')
+        && !text.ends_with('-')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+static FILES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/posts");
+
+/// Validate all the posts, returning every error found.
+pub fn validate_posts() -> Vec<PostError> {
+    let mut errors = Vec::new();
+    let mut ids = HashMap::new();
+    let mut series_parts = HashMap::new();
+    for entry in FILES.find("**/*.mdx").unwrap() {
+        let Some(file) = entry.as_file() else {
+            continue;
+        };
+        match parse_post(file) {
+            Ok((id, post)) => {
+                let path = format!("posts/{}", file.path().display());
+                let key_error = |key: &str, message: String| PostError {
+                    path: path.clone(),
+                    line: file
+                        .contents_utf8()
+                        .and_then(split_front_matter)
+                        .and_then(|front_matter| find_key_line(front_matter, key)),
+                    message,
+                };
+
+                let cover = post.front_matter.cover.as_deref();
+                if let Some(cover) = cover.and_then(|cover| cover.strip_prefix('/')) {
+                    if !Path::new(env!("CARGO_MANIFEST_DIR")).join(cover).is_file() {
+                        errors.push(key_error(
+                            "cover",
+                            format!("cover image `/{cover}` does not exist"),
+                        ));
+                    }
+                }
+                for tag in post.front_matter.tags.iter().filter(|tag| !is_slug(tag)) {
+                    errors.push(key_error(
+                        "tags",
+                        format!(
+                            "invalid tag `{tag}`, it should only contain lowercase letters, digits and `-`"
+                        ),
+                    ));
+                }
+                if let Some(series) = post
+                    .front_matter
+                    .series
+                    .as_deref()
+                    .filter(|series| !is_slug(series))
+                {
+                    errors.push(key_error(
+                        "series",
+                        format!(
+                            "invalid series `{series}`, it should only contain lowercase letters, digits and `-`"
+                        ),
+                    ));
+                }
+                match (&post.front_matter.series, post.front_matter.series_order) {
+                    (None, Some(_)) => errors.push(key_error(
+                        "series_order",
+                        "`series_order` is set but the post is not in a series".to_string(),
+                    )),
+                    (Some(series), Some(order)) => {
+                        if let Some(other) =
+                            series_parts.insert((series.clone(), order), file.path())
+                        {
+                            errors.push(key_error(
+                                "series_order",
+                                format!(
+                                    "part {order} of series `{series}` is also used by `posts/{}`",
+                                    other.display()
+                                ),
+                            ));
+                        }
+                    }
+                    _ => {}
+                }
+                if let Some(other) = ids.insert(id.clone(), file.path()) {
+                    errors.push(PostError {
+                        path: path.clone(),
+                        line: None,
+                        message: format!(
+                            "duplicate post id `{id}`, also used by `posts/{}`",
+                            other.display()
+                        ),
+                    });
+                }
+            }
+            Err(errs) => errors.extend(errs),
+        }
+        errors.extend(validate_slides(file));
+    }
+    errors
+}
+
+/// All the posts, indexed by filename. Posts that fail to parse are skipped. Use
+/// [`validate_posts`] to report the errors.
+///
+/// Unpublished posts are only included if [`preview_enabled`] is set.
+pub static POSTS: LazyLock<HashMap<String, ParseRes<PostMetadata>>> = LazyLock::new(|| {
+    FILES
+        .find("**/*.mdx")
+        .unwrap()
+        .filter_map(|entry| parse_post(entry.as_file()?).ok())
+        .filter(|(_, post)| preview_enabled() || post.front_matter.is_published())
+        .collect()
+});
+
+/// The text of every post in [`POSTS`], indexed by filename. This is used for searching.
+pub static POST_TEXT: LazyLock<HashMap<String, String>> = LazyLock::new(|| {
+    FILES
+        .find("**/*.mdx")
+        .unwrap()
+        .filter_map(|entry| {
+            let file = entry.as_file()?;
+            let id = file.path().file_stem()?.to_str()?;
+            if !POSTS.contains_key(id) {
+                return None;
+            }
+            let text = prose_text(split_body(file.contents_utf8()?));
+            Some((id.to_string(), text))
+        })
+        .collect()
+});
+
+/// The source of the post with the given id, including the front matter.
+pub fn post_source(id: &str) -> Option<&'static str> {
+    FILES
+        .find("**/*.mdx")
+        .unwrap()
+        .filter_map(|entry| entry.as_file())
+        .find(|file| file.path().file_stem().and_then(|stem| stem.to_str()) == Some(id))
+        .and_then(|file| file.contents_utf8())
+}
+
+/// The maximum number of related posts that are shown for a post.
+const RELATED_POSTS: usize = 3;
+
+/// Fill in the fields of the posts that link to other posts: the chronologically previous and next
+/// posts, and the posts that are related by their tags.
+pub fn link_posts(index: &mut HashMap<String, PostMetadata>) {
+    let mut by_date = index
+        .values()
+        .map(|post| (post.date, post.filename.clone()))
+        .collect::<Vec<_>>();
+    by_date.sort();
+
+    let related = index
+        .values()
+        .map(|post| {
+            let mut scored = index
+                .values()
+                .filter(|other| other.filename != post.filename)
+                .map(|other| {
+                    let shared = other
+                        .tags
+                        .iter()
+                        .filter(|tag| post.tags.contains(tag))
+                        .count();
+                    (shared, other.date, other.filename.clone())
+                })
+                .filter(|(shared, _, _)| *shared > 0)
+                .collect::<Vec<_>>();
+            // Most shared tags first, then the most recent.
+            scored.sort_by(|a, b| b.cmp(a));
+            let ids = scored.into_iter().take(RELATED_POSTS).map(|(_, _, id)| id);
+            (post.filename.clone(), ids.collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+
+    for (i, (_, id)) in by_date.iter().enumerate() {
+        let post = index.get_mut(id).expect("post should be in index");
+        post.previous = i.checked_sub(1).map(|i| by_date[i].1.clone());
+        post.next = by_date.get(i + 1).map(|(_, id)| id.clone());
+    }
+    for (id, related) in related {
+        index.get_mut(&id).expect("post should be in index").related = related;
+    }
+}
+
+/// Serialize the summaries of all the posts so that they can be embedded into the pages that list
+/// posts.
+pub fn post_index_json() -> String {
+    serde_json::to_string(&posts_by_date()).expect("failed to serialize post index")
+}
+
+/// Everything that the page of the post with the given id shows, except for the server fragments
+/// which are recorded while rendering it.
+pub fn post_page(id: &str) -> Option<PostPage> {
+    let post = POST_INDEX.get(id)?.clone();
+    let linked = posts_by_date()
+        .into_iter()
+        .filter(|other| {
+            post.previous.as_ref() == Some(&other.id)
+                || post.next.as_ref() == Some(&other.id)
+                || post.related.contains(&other.id)
+                || (post.series.is_some() && other.series == post.series)
+        })
+        .collect();
+    Some(PostPage {
+        post,
+        linked,
+        body: POSTS.get(id)?.body.clone(),
+        fragments: Vec::new(),
+    })
+}
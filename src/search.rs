@@ -0,0 +1,260 @@
+//! Full-text search over the posts. The index is built during SSR and loaded by the client when
+//! searching.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sycamore::prelude::*;
+
+use crate::pages::post::PostDate;
+
+/// Path of the search index on the site.
+pub static SEARCH_INDEX_PATH: &str = "/search.json";
+
+/// Weights of a term depending on where it appears in the post.
+#[cfg_ssr]
+const TITLE_WEIGHT: u32 = 10;
+#[cfg_ssr]
+const TAG_WEIGHT: u32 = 5;
+#[cfg_ssr]
+const DESC_WEIGHT: u32 = 3;
+#[cfg_ssr]
+const BODY_WEIGHT: u32 = 1;
+
+/// Number of bytes of text shown before the first match in a snippet, and the length of a snippet.
+const SNIPPET_CONTEXT: usize = 60;
+const SNIPPET_LEN: usize = 200;
+
+/// Maximum number of bytes of text that are kept for each post to build snippets from.
+#[cfg_ssr]
+const DOC_TEXT_LEN: usize = 1000;
+
+/// A post that can be found by searching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchDoc {
+    pub id: String,
+    pub title: String,
+    pub date: PostDate,
+    /// The description of the post followed by the start of its text, used for snippets. The rest
+    /// of the text is left out to keep the index small, so matches further into the post get a
+    /// snippet from the start of the text.
+    pub text: String,
+}
+
+/// An inverted index from terms to the posts containing them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    pub docs: Vec<SearchDoc>,
+    /// Every term along with the positions in `docs` of the posts containing it and the weight of
+    /// the term in each post. This is sorted by term so that prefixes can be found with a binary
+    /// search.
+    pub terms: Vec<(String, Vec<(u32, u32)>)>,
+}
+
+/// A post that matches the search query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub id: String,
+    pub title: String,
+    pub date: PostDate,
+    /// The text around the first match, split into parts that are highlighted or not.
+    pub snippet: Vec<(String, bool)>,
+}
+
+/// Split the text into lowercase terms.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    words(text).map(|(_, word)| word.to_lowercase())
+}
+
+/// The words in the text along with their byte offsets.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+impl SearchIndex {
+    /// Build the index from all the posts.
+    #[cfg_ssr]
+    pub fn build() -> Self {
+        use std::collections::BTreeMap;
+
+        let mut terms = BTreeMap::<String, BTreeMap<u32, u32>>::new();
+        let docs = crate::pages::post::posts_by_date()
+            .into_iter()
+            .enumerate()
+            .map(|(i, post)| {
+                let mut add = |text: &str, weight: u32| {
+                    for term in tokenize(text) {
+                        *terms.entry(term).or_default().entry(i as u32).or_default() += weight;
+                    }
+                };
+                let body = crate::posts::POST_TEXT
+                    .get(&post.id)
+                    .cloned()
+                    .unwrap_or_default();
+                add(&post.title, TITLE_WEIGHT);
+                for tag in &post.tags {
+                    add(tag, TAG_WEIGHT);
+                }
+                add(&post.desc, DESC_WEIGHT);
+                add(&body, BODY_WEIGHT);
+
+                let text = if post.desc.is_empty() {
+                    body
+                } else {
+                    format!("{}\n{body}", post.desc)
+                };
+                SearchDoc {
+                    id: post.id,
+                    title: post.title,
+                    date: post.date,
+                    text: truncate_text(text),
+                }
+            })
+            .collect();
+        let terms = terms
+            .into_iter()
+            .map(|(term, postings)| (term, postings.into_iter().collect()))
+            .collect();
+        SearchIndex { docs, terms }
+    }
+
+    /// The terms in the index that start with `prefix`.
+    fn prefix_matches<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = &'a (String, Vec<(u32, u32)>)> {
+        let start = self
+            .terms
+            .partition_point(|(term, _)| term.as_str() < prefix);
+        self.terms[start..]
+            .iter()
+            .take_while(move |(term, _)| term.starts_with(prefix))
+    }
+
+    /// Find the posts that contain every term in the query, either exactly or as a prefix, sorted
+    /// by relevance.
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let mut query = tokenize(query).collect::<Vec<_>>();
+        query.sort();
+        query.dedup();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores = HashMap::<u32, (usize, u32)>::new();
+        for term in &query {
+            let mut term_scores = HashMap::<u32, u32>::new();
+            for (matched, postings) in self.prefix_matches(term) {
+                // Exact matches rank higher than prefix matches.
+                let boost = if matched == term { 2 } else { 1 };
+                for &(doc, weight) in postings {
+                    *term_scores.entry(doc).or_default() += weight * boost;
+                }
+            }
+            for (doc, score) in term_scores {
+                let entry = scores.entry(doc).or_default();
+                entry.0 += 1;
+                entry.1 += score;
+            }
+        }
+
+        let mut results = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == query.len())
+            .map(|(doc, (_, score))| (doc, score))
+            .collect::<Vec<_>>();
+        results.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        results
+            .into_iter()
+            .filter_map(|(doc, _)| self.docs.get(doc as usize))
+            .map(|doc| SearchResult {
+                id: doc.id.clone(),
+                title: doc.title.clone(),
+                date: doc.date,
+                snippet: snippet(&doc.text, &query),
+            })
+            .collect()
+    }
+}
+
+/// The part of the text around the first word matching the query, with the matching words
+/// highlighted.
+fn snippet(text: &str, query: &[String]) -> Vec<(String, bool)> {
+    let is_match = |word: &str| {
+        let word = word.to_lowercase();
+        query.iter().any(|term| word.starts_with(term.as_str()))
+    };
+    let floor_char_boundary = |mut i: usize| {
+        while !text.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    };
+
+    let first = words(text)
+        .find(|(_, word)| is_match(word))
+        .map_or(0, |(i, _)| i);
+    let mut start = floor_char_boundary(first.saturating_sub(SNIPPET_CONTEXT));
+    // Do not start in the middle of a word.
+    if start > 0 {
+        if let Some(space) = text[start..first].find(char::is_whitespace) {
+            start += space + 1;
+        }
+    }
+    let mut end = floor_char_boundary((start + SNIPPET_LEN).min(text.len()));
+    if end < text.len() {
+        if let Some(space) = text[start..end].rfind(char::is_whitespace) {
+            end = start + space;
+        }
+    }
+    let window = &text[start..end];
+
+    let mut parts = Vec::new();
+    let mut push = |part: &str, highlight: bool| {
+        if !part.is_empty() {
+            parts.push((part.replace('\n', " "), highlight));
+        }
+    };
+    if start > 0 {
+        push("…", false);
+    }
+    let mut last = 0;
+    for (i, word) in words(window) {
+        if is_match(word) {
+            push(&window[last..i], false);
+            push(word, true);
+            last = i + word.len();
+        }
+    }
+    push(&window[last..], false);
+    if end < text.len() {
+        push("…", false);
+    }
+    parts
+}
+
+/// Cut the text off at the last whitespace before [`DOC_TEXT_LEN`] bytes.
+#[cfg_ssr]
+fn truncate_text(mut text: String) -> String {
+    if text.len() > DOC_TEXT_LEN {
+        let mut end = DOC_TEXT_LEN;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let end = text[..end].rfind(char::is_whitespace).unwrap_or(end);
+        text.truncate(end);
+    }
+    text
+}
+
+/// Write the search index into `out_dir`.
+#[cfg_ssr]
+pub fn write_index(out_dir: &std::path::Path) -> std::io::Result<()> {
+    let json = serde_json::to_string(&SearchIndex::build()).map_err(std::io::Error::other)?;
+    std::fs::write(
+        out_dir.join(SEARCH_INDEX_PATH.trim_start_matches('/')),
+        json,
+    )
+}
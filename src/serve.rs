@@ -0,0 +1,215 @@
+//! A development server for the generated site. The site is rebuilt when its sources change, and
+//! open pages are reloaded over a WebSocket.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::{header, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use notify::{RecursiveMode, Watcher};
+use tokio::process::Command;
+use tokio::sync::broadcast;
+
+use crate::links::{percent_decode, resolve};
+
+/// Path of the WebSocket that reload events are sent on.
+static RELOAD_PATH: &str = "/.well-known/blog/reload";
+
+/// Script that is injected into every page to reload it when the site is rebuilt.
+fn reload_script() -> String {
+    format!(
+        r#"<script>
+(() => {{
+    const connect = () => {{
+        const protocol = location.protocol === "https:" ? "wss" : "ws";
+        const socket = new WebSocket(`${{protocol}}://${{location.host}}{RELOAD_PATH}`);
+        socket.onmessage = () => location.reload();
+        socket.onclose = () => setTimeout(connect, 1000);
+    }};
+    connect();
+}})();
+</script>"#
+    )
+}
+
+/// The files and directories, relative to the crate root, that trigger a rebuild when they change.
+/// The site configuration is watched as well, wherever it is.
+static WATCHED_PATHS: &[&str] = &[
+    "posts",
+    "assets",
+    "src",
+    "index.html",
+    "Cargo.toml",
+    "Trunk.toml",
+    "tailwind.config.js",
+];
+
+/// How long to wait for more changes before rebuilding, e.g. when an editor writes several files.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+#[derive(Clone)]
+struct ServerState {
+    /// The directory that is served.
+    dir: Arc<PathBuf>,
+    reload: broadcast::Sender<()>,
+}
+
+fn content_type(ext: &str) -> &'static str {
+    match ext {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "wasm" => "application/wasm",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "mp4" => "video/mp4",
+        "woff2" => "font/woff2",
+        "txt" => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Respond with the contents of the file, injecting the reload script into HTML pages.
+async fn file_response(path: &Path, status: StatusCode) -> Response {
+    let Ok(mut body) = tokio::fs::read(path).await else {
+        return (StatusCode::NOT_FOUND, "not found").into_response();
+    };
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if ext == "html" {
+        let html = String::from_utf8_lossy(&body);
+        let end = html.rfind("</body>").unwrap_or(html.len());
+        body = format!("{}{}{}", &html[..end], reload_script(), &html[end..]).into_bytes();
+    }
+    (
+        status,
+        [
+            (header::CONTENT_TYPE, content_type(ext)),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Serve the file for the URL, with the same clean URLs as the deployed site, e.g. `/about` is
+/// served by `about.html`. Missing pages are served by `404.html`.
+async fn serve_file(State(state): State<ServerState>, uri: Uri) -> Response {
+    let path = percent_decode(uri.path());
+    if path.split('/').any(|part| part == "..") {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    match resolve(&state.dir, &path) {
+        Some(file) => file_response(&state.dir.join(file), StatusCode::OK).await,
+        None => file_response(&state.dir.join("404.html"), StatusCode::NOT_FOUND).await,
+    }
+}
+
+/// Send a message on the WebSocket every time the site is rebuilt.
+async fn reload_socket(State(state): State<ServerState>, ws: WebSocketUpgrade) -> Response {
+    let mut reload = state.reload.subscribe();
+    ws.on_upgrade(move |mut socket| async move {
+        loop {
+            tokio::select! {
+                event = reload.recv() => {
+                    if matches!(event, Err(broadcast::error::RecvError::Closed))
+                        || socket.send(Message::Text("reload".into())).await.is_err()
+                    {
+                        break;
+                    }
+                }
+                message = socket.recv() => {
+                    if !matches!(message, Some(Ok(_))) {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Rebuild the site with Trunk, which also runs the SSR build through its hooks. Returns whether
+/// the build succeeded.
+async fn rebuild() -> bool {
+    eprintln!("Rebuilding site");
+    let status = Command::new("trunk")
+        .arg("build")
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .await;
+    match status {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            eprintln!("error: rebuilding site failed with {status}");
+            false
+        }
+        Err(err) => {
+            eprintln!("error: could not run `trunk build`: {err}");
+            false
+        }
+    }
+}
+
+/// Watch the sources of the site, rebuilding the site and sending a reload event when they change.
+/// The returned watcher needs to be kept alive.
+fn watch(reload: broadcast::Sender<()>) -> notify::Result<notify::RecommendedWatcher> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| !event.kind.is_access()) {
+            let _ = tx.send(());
+        }
+    })?;
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let paths = WATCHED_PATHS
+        .iter()
+        .map(|path| root.join(path))
+        .chain([crate::config::site_config_path()]);
+    for path in paths.filter(|path| path.exists()) {
+        watcher.watch(&path, RecursiveMode::Recursive)?;
+    }
+
+    tokio::spawn(async move {
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            if rebuild().await {
+                let _ = reload.send(());
+            }
+        }
+    });
+    Ok(watcher)
+}
+
+/// Build the site and serve `dir` on `port`, rebuilding when the sources of the site change.
+pub async fn serve(dir: &Path, port: u16) -> std::io::Result<()> {
+    let (reload, _) = broadcast::channel(16);
+    let state = ServerState {
+        dir: Arc::new(dir.to_path_buf()),
+        reload: reload.clone(),
+    };
+
+    if !rebuild().await {
+        eprintln!("warning: serving the previous build");
+    }
+    let _watcher = watch(reload).map_err(std::io::Error::other)?;
+
+    let app = Router::new()
+        .route(RELOAD_PATH, get(reload_socket))
+        .fallback(serve_file)
+        .with_state(state);
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("Serving `{}` on http://{addr}", dir.display());
+    axum::serve(listener, app).await
+}
@@ -2,6 +2,7 @@
 //!
 //! Eventually consider upstreaming this into Sycamore or into a seperate crate.
 
+#[cfg_ssr]
 use std::{
     collections::HashMap,
     sync::{LazyLock, Mutex},
@@ -9,10 +10,19 @@ use std::{
 
 use sycamore::prelude::*;
 
-type ServerComponentMap = HashMap<String, String>;
+/// Context value for collecting the server components rendered in the current scope, as pairs of
+/// id and rendered content. This lets the build write out the components of each page.
+#[cfg_ssr]
+#[derive(Debug, Clone, Default)]
+pub struct ServerComponents(std::rc::Rc<std::cell::RefCell<Vec<(String, String)>>>);
 
-/// A map from server component ids to the rendered content.
-pub static SERVER_COMPONENTS: LazyLock<Mutex<ServerComponentMap>> = LazyLock::new(Default::default);
+#[cfg_ssr]
+impl ServerComponents {
+    /// Take all the server components that were rendered so far.
+    pub fn take(&self) -> Vec<(String, String)> {
+        self.0.take()
+    }
+}
 
 /// Only run the component at build-time/during SSR. On the client side, if not hydrating, this
 /// will fetch the component HTML over HTTP.
@@ -27,7 +37,7 @@ pub fn ServerOnly(
 ) -> View {
     is_ssr! {
         let _ = on_load;
-        // Render the children, as well as adding it to SERVER_COMPONENTS.
+        // Render the children, as well as adding it to the recorded server components.
         let mut children = Some(children);
         let view = view! {
             sycamore::web::NoHydrate {
@@ -35,25 +45,45 @@ pub fn ServerOnly(
             }
         };
         let html = sycamore::render_to_string_in_scope(|| view);
-        SERVER_COMPONENTS.lock().unwrap().insert(id.clone(), html.clone());
+        if let Some(components) = try_use_context::<ServerComponents>() {
+            components.0.borrow_mut().push((id.clone(), html.clone()));
+        }
 
         view! {
             server-component(data-component=id, dangerously_set_inner_html=html)
         }
     }
     is_not_ssr! {
-        use gloo_net::http::Request;
+        use std::rc::Rc;
         use sycamore::web::{AsHtmlNode, ViewHtmlNode, wasm_bindgen::JsCast};
 
         let _ = children;
 
         let container = create_node_ref();
 
-        // Fetch the component HTML over HTTP if we are not hydrating.
-        if !sycamore::web::is_hydrating() {
-            let url = format!("/server_components/{id}.html");
+        // Nothing needs to be fetched when hydrating.
+        if sycamore::web::is_hydrating() {
+            return view! {
+                server-component(data-component=id, r#ref=container)
+            };
+        }
+
+        // Fetch the component HTML over HTTP.
+        let error = create_signal(None::<String>);
+        let on_load = on_load.map(Rc::<dyn Fn()>::from);
+        let url = format!("/server_components/{id}.html");
+        let load = move || {
+            let url = url.clone();
+            let on_load = on_load.clone();
             sycamore::futures::create_suspense_task(async move {
-                let html = Request::get(&url).send().await.expect("could not send HTTP request").text().await.expect("could not get text from response");
+                let html = match crate::fetch::fetch_text(&url).await {
+                    Ok(html) => html,
+                    Err(err) => {
+                        error.set(Some(err.to_string()));
+                        return;
+                    }
+                };
+                error.set(None);
                 sycamore::web::DomNode::from_web_sys(container.get()).set_inner_html(html.into());
 
                 // Recreate all the script tags so that they run.
@@ -66,13 +96,24 @@ pub fn ServerOnly(
                 }
 
                 if let Some(on_load) = on_load {
-                    on_mount(on_load);
+                    on_mount(move || on_load());
                 }
             });
-        }
+        };
+        load();
+        let load = Rc::new(load);
 
         view! {
             server-component(data-component=id, r#ref=container)
+            (match error.get_clone() {
+                Some(error) => {
+                    let load = Rc::clone(&load);
+                    view! {
+                        crate::fetch::FetchErrorView(what="component", error=error, on_retry=move || load())
+                    }
+                }
+                None => view! {},
+            })
         }
     }
 }
@@ -91,3 +132,94 @@ pub fn ServerTitle<S: Into<String>>(title: S) -> View {
         view! {}
     }
 }
+
+/// A map from keys to the server fragments that were recorded under that key, in render order.
+#[cfg_ssr]
+pub static SERVER_FRAGMENTS: LazyLock<Mutex<HashMap<String, Vec<String>>>> =
+    LazyLock::new(Default::default);
+
+/// Context value for recording server fragments during SSR.
+#[cfg_ssr]
+#[derive(Debug, Clone)]
+struct RecordFragments(String);
+
+/// Context value for replaying server fragments on the client.
+#[cfg_not_ssr]
+#[derive(Debug, Clone, Copy)]
+struct ReplayFragments(Signal<std::collections::VecDeque<String>>);
+
+/// Record all server fragments rendered in the current scope under `key`. The fragments can be
+/// retrieved with [`take_fragments`] once rendering is done.
+#[cfg_ssr]
+pub fn record_fragments(key: impl Into<String>) {
+    provide_context(RecordFragments(key.into()));
+}
+
+/// Take all the server fragments that were recorded under `key`.
+#[cfg_ssr]
+pub fn take_fragments(key: &str) -> Vec<String> {
+    SERVER_FRAGMENTS
+        .lock()
+        .unwrap()
+        .remove(key)
+        .unwrap_or_default()
+}
+
+/// Replay server fragments in the current scope, in the same order as they were recorded.
+#[cfg_not_ssr]
+pub fn replay_fragments(fragments: Vec<String>) {
+    provide_context(ReplayFragments(create_signal(fragments.into())));
+}
+
+/// A server fragment is a piece of HTML that can only be rendered at build-time, such as
+/// highlighted code. This should be rendered inside a `NoHydrate` since the client does not
+/// reproduce it when hydrating. On the client, use [`next_fragment`] instead.
+///
+/// This records the HTML if [`record_fragments`] was called and returns it.
+#[cfg_ssr]
+pub fn server_fragment(html: String) -> String {
+    if let Some(RecordFragments(key)) = try_use_context::<RecordFragments>() {
+        SERVER_FRAGMENTS
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_default()
+            .push(html.clone());
+    }
+    html
+}
+
+/// Get the next fragment passed to [`replay_fragments`] on the client, or an empty string if there
+/// is none, e.g. when hydrating. This is the client side counterpart of [`server_fragment`].
+#[cfg_not_ssr]
+pub fn next_fragment() -> String {
+    try_use_context::<ReplayFragments>()
+        .and_then(|fragments| fragments.0.update(|fragments| fragments.pop_front()))
+        .unwrap_or_default()
+}
+
+/// Render the children to plain text. This is useful for components that need to process their
+/// contents at build-time.
+#[cfg_ssr]
+pub fn render_text(children: Children) -> String {
+    let html = sycamore::render_to_string_in_scope(|| children.call());
+
+    // Strip out the tags and comments, then decode the entities.
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html.as_str();
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = match rest[start..].find('>') {
+            Some(end) => &rest[start + end + 1..],
+            None => "",
+        };
+    }
+    text.push_str(rest);
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
@@ -1,5 +1,7 @@
 use sycamore::prelude::*;
 
+use crate::config::site;
+use crate::pages::post::PostDate;
 use crate::Routes;
 
 /// Context value for setting the document title.
@@ -12,10 +14,174 @@ pub fn set_title(title: impl Into<String>) {
     use_context::<Title>().0.set(title.into());
 }
 
+/// Metadata about the current page that is rendered into the head during SSR, for search engines
+/// and link previews.
+#[derive(Debug, Clone, Default)]
+struct PageMeta {
+    description: Option<String>,
+    /// Path of the canonical URL of the page.
+    canonical: Option<String>,
+    /// Path or URL of the image used for link previews.
+    image: Option<String>,
+    article: Option<ArticleMeta>,
+}
+
+#[derive(Debug, Clone)]
+struct ArticleMeta {
+    published_time: PostDate,
+    tags: Vec<String>,
+}
+
+/// Context value for setting the [`PageMeta`].
+#[derive(Debug, Clone, Copy)]
+struct Meta(Signal<PageMeta>);
+
+/// Set the description of the page. Defaults to the description of the site.
+pub fn set_description(description: impl Into<String>) {
+    let description = description.into();
+    use_context::<Meta>()
+        .0
+        .update(|meta| meta.description = Some(description));
+}
+
+/// Set the canonical path of the page, e.g. `/post/foo`.
+pub fn set_canonical(path: impl Into<String>) {
+    let path = path.into();
+    use_context::<Meta>()
+        .0
+        .update(|meta| meta.canonical = Some(path));
+}
+
+/// Set the image used for link previews. This can either be a path on the site or a full URL.
+pub fn set_image(image: impl Into<String>) {
+    let image = image.into();
+    use_context::<Meta>()
+        .0
+        .update(|meta| meta.image = Some(image));
+}
+
+/// Mark the page as an article, e.g. a post.
+pub fn set_article(published_time: PostDate, tags: Vec<String>) {
+    use_context::<Meta>().0.update(|meta| {
+        meta.article = Some(ArticleMeta {
+            published_time,
+            tags,
+        })
+    });
+}
+
+/// Render a `<meta name=...>` tag.
+fn name_meta(name: &'static str, content: String) -> View {
+    sycamore::web::tags::meta()
+        .name(name)
+        .content(content)
+        .into()
+}
+
+/// Render a `<meta property=...>` tag, as used by Open Graph.
+fn property_meta(property: &'static str, content: String) -> View {
+    sycamore::web::tags::meta()
+        .attr("property", property)
+        .content(content)
+        .into()
+}
+
+/// Render the meta tags for the page.
+fn meta_tags(title: String, meta: PageMeta) -> View {
+    let absolute = |url: String| {
+        if url.starts_with('/') {
+            format!("{}{url}", site().base_url)
+        } else {
+            url
+        }
+    };
+    let description = meta
+        .description
+        .unwrap_or_else(|| site().description.clone());
+
+    let mut tags = vec![
+        name_meta("description", description.clone()),
+        property_meta("og:site_name", site().title.clone()),
+        property_meta("og:title", title.clone()),
+        property_meta("og:description", description.clone()),
+        name_meta("twitter:title", title),
+        name_meta("twitter:description", description),
+    ];
+    if let Some(path) = meta.canonical {
+        let url = absolute(path);
+        tags.push(
+            sycamore::web::tags::link()
+                .rel("canonical")
+                .href(url.clone())
+                .into(),
+        );
+        tags.push(property_meta("og:url", url));
+    }
+    match meta.image {
+        Some(image) => {
+            let image = absolute(image);
+            tags.push(property_meta("og:image", image.clone()));
+            tags.push(name_meta("twitter:card", "summary_large_image".to_string()));
+            tags.push(name_meta("twitter:image", image));
+        }
+        None => tags.push(name_meta("twitter:card", "summary".to_string())),
+    }
+    match meta.article {
+        Some(article) => {
+            tags.push(property_meta("og:type", "article".to_string()));
+            tags.push(property_meta(
+                "article:published_time",
+                format!("{}T00:00:00Z", article.published_time),
+            ));
+            for tag in article.tags {
+                tags.push(property_meta("article:tag", tag));
+            }
+        }
+        None => tags.push(property_meta("og:type", "website".to_string())),
+    }
+    View::from(tags)
+}
+
+/// Context value for JSON data that is embedded into the page during SSR, as pairs of element id
+/// and JSON string.
+#[derive(Debug, Clone, Copy)]
+struct Embedded(Signal<Vec<(String, String)>>);
+
+/// Embed JSON data into the page during SSR. The data can be read back on the client with
+/// [`read_embedded`] without having to fetch it separately.
+#[cfg_ssr]
+pub fn embed_json(id: impl Into<String>, json: String) {
+    use_context::<Embedded>()
+        .0
+        .update(|embedded| embedded.push((id.into(), json)));
+}
+
+/// Escape `<` so that the data cannot close the script tag that it is embedded in.
+fn escape_embedded(json: &str) -> String {
+    json.replace('<', "\\u003c")
+}
+
+/// Read JSON data that was embedded into the page with [`embed_json`].
+#[cfg_not_ssr]
+pub fn read_embedded(id: &str) -> Option<String> {
+    document().get_element_by_id(id)?.text_content()
+}
+
 #[component(inline_props)]
 pub fn Shell(children: Children) -> View {
     let title = Title(create_signal(String::new()));
     provide_context(title);
+    let embedded = Embedded(create_signal(Vec::new()));
+    provide_context(embedded);
+    let meta = Meta(create_signal(PageMeta::default()));
+    provide_context(meta);
+
+    is_ssr! {
+        embed_json(
+            crate::config::SITE_CONFIG_ID,
+            serde_json::to_string(site()).expect("failed to serialize site config"),
+        );
+    }
 
     if is_not_ssr!() {
         create_effect(move || {
@@ -29,6 +195,23 @@ pub fn Shell(children: Children) -> View {
     // Call the children now so that we can get the title before we render the head.
     let children = children.call();
     let title_static = title.0.get_clone();
+    let meta_tags = meta_tags(title_static.clone(), meta.0.get_clone());
+    let analytics = match &site().analytics {
+        Some(analytics) => {
+            let script = analytics.script.clone();
+            let website_id = analytics.website_id.clone();
+            view! {
+                script(defer=true, src=script, data-website-id=website_id)
+            }
+        }
+        None => view! {},
+    };
+    let embedded = embedded
+        .0
+        .get_clone()
+        .into_iter()
+        .map(|(id, json)| (id, escape_embedded(&json)))
+        .collect::<Vec<_>>();
 
     view! {
         html(lang="en") {
@@ -37,9 +220,12 @@ pub fn Shell(children: Children) -> View {
                     meta(charset="utf-8")
                     meta(name="viewport", content="width=device-width, initial-scale=1")
 
-                    meta(name="description", content="Luke Chu's personal website. I write about programming, technology, physics, and other things I find interesting.")
-
                     title { (title_static) }
+                    (meta_tags)
+
+                    link(rel="alternate", r#type="application/atom+xml", title=site().title.clone(), href="/feed.xml")
+                    link(rel="alternate", r#type="application/rss+xml", title=site().title.clone(), href="/rss.xml")
+                    link(rel="alternate", r#type="application/feed+json", title=site().title.clone(), href="/feed.json")
 
                     link(rel="preload", href="/blog.js", r#as="script", crossorigin="")
                     link(rel="preload", href="/blog_bg.wasm", r#as="fetch", crossorigin="")
@@ -48,21 +234,17 @@ pub fn Shell(children: Children) -> View {
                     }
 
                     link(rel="stylesheet", href="/index.css")
-                    link(rel="stylesheet", href="/assets/prism.css")
-                    script(r#type="text/javascript", src="/assets/prism.js")
-                    script(id="MathJax-script", src="https://cdn.jsdelivr.net/npm/mathjax@3/es5/tex-mml-chtml.js")
-                    // Analytics
-                    script(defer=true, src="https://cloud.umami.is/script.js", data-website-id="4ea18895-1c63-4382-b58f-094c0ba1e8e8")
+                    link(rel="stylesheet", href="/highlight.css")
+                    (analytics)
 
-                    sycamore::web::HydrationScript {}
-
-                    (if option_env!("TRUNK_AUTO_RELOAD").is_some() {
-                        view! {
-                            script(dangerously_set_inner_html=AUTORELOAD)
+                    Indexed(
+                        list=embedded,
+                        view=|(id, json)| view! {
+                            script(r#type="application/json", id=id, dangerously_set_inner_html=json)
                         }
-                    } else {
-                        view! {}
-                    })
+                    )
+
+                    sycamore::web::HydrationScript {}
                 }
             }
             body {
@@ -72,15 +254,75 @@ pub fn Shell(children: Children) -> View {
     }
 }
 
+/// The canonical path of the route, if there is one.
+fn canonical_path(route: &Routes) -> Option<String> {
+    match route {
+        Routes::Home | Routes::Page(1) => Some("/".to_string()),
+        Routes::Page(n) => Some(format!("/page/{n}")),
+        Routes::Archive => Some("/archive".to_string()),
+        Routes::About => Some("/about".to_string()),
+        Routes::Post(id) => Some(format!("/post/{id}")),
+        Routes::Tags => Some("/tags".to_string()),
+        Routes::Tag(tag) => Some(format!("/tags/{tag}")),
+        Routes::Series(name) => Some(format!("/series/{name}")),
+        Routes::Search => Some("/search".to_string()),
+        Routes::NotFound => None,
+    }
+}
+
+/// Whether the page of the route lists posts. Only these pages get the summaries of all the posts.
+pub fn lists_posts(route: &Routes) -> bool {
+    matches!(
+        route,
+        Routes::Home
+            | Routes::Page(_)
+            | Routes::Archive
+            | Routes::Tags
+            | Routes::Tag(_)
+            | Routes::Series(_)
+    )
+}
+
+/// Render the page of a route for which [`lists_posts`] is true.
+fn listing_page(route: Routes) -> View {
+    match route {
+        Routes::Home => view! {
+            crate::pages::home::Home()
+        },
+        Routes::Page(n) => view! {
+            crate::pages::home::HomePage(page=n)
+        },
+        Routes::Archive => view! {
+            crate::pages::archive::Archive()
+        },
+        Routes::Tags => view! {
+            crate::pages::tags::Tags()
+        },
+        Routes::Tag(tag) => view! {
+            crate::pages::tags::TagView(tag=tag)
+        },
+        Routes::Series(name) => view! {
+            crate::pages::series::SeriesView(name=name)
+        },
+        _ => view! {
+            NotFound()
+        },
+    }
+}
+
 #[component]
 pub fn App(route: ReadSignal<Routes>) -> View {
+    if let Some(path) = canonical_path(&route.get_clone()) {
+        set_canonical(path);
+    }
+
     view! {
         div(class="app flex flex-col min-h-screen text-slate-200 bg-slate-950 text-base") {
             Header()
             main(class="mt-6 mx-3 flex-grow") {
                 (match route.get_clone() {
-                    Routes::Home => view! {
-                        crate::pages::home::Home()
+                    listing if lists_posts(&listing) => view! {
+                        crate::pages::post::WithPostIndex(route=listing, view=listing_page)
                     },
                     Routes::About => view! {
                         crate::pages::about::About()
@@ -88,7 +330,10 @@ pub fn App(route: ReadSignal<Routes>) -> View {
                     Routes::Post(id) => view! {
                         crate::pages::post::PostView(id=id)
                     },
-                    Routes::NotFound => view! {
+                    Routes::Search => view! {
+                        crate::pages::search::Search()
+                    },
+                    _ => view! {
                         NotFound()
                     },
                 })
@@ -100,16 +345,41 @@ pub fn App(route: ReadSignal<Routes>) -> View {
 
 #[component]
 fn Header() -> View {
+    let site = site();
+    let header_title = site.header_title.clone();
+    let title = site.title.clone();
+    let nav = site
+        .nav
+        .iter()
+        .map(|link| {
+            let label = link.label.clone();
+            let href = link.href.clone();
+            view! {
+                div { a(class="hover:underline", href=href) { (label) } }
+            }
+        })
+        .collect::<Vec<View>>();
+
     view! {
         header(class="z-50 p-2 bg-inherit border-b-2 border-slate-700 text-sm font-mono text-red-200 sm:px-10 md:px-20 lg:px-40 xl:px-60") {
             nav(class="flex flex-row justify-between items-center") {
                 div(class="self-start hover:underline font-bold") {
-                    a(class="hidden sm:inline", href="/") { "$ cd /home/lukechu" }
-                    a(class="inline sm:hidden", href="/") { "lukechu" }
+                    a(class="hidden sm:inline", href="/") { (header_title) }
+                    a(class="inline sm:hidden", href="/") { (title) }
                 }
-                div(class="self-end flex flex-row gap-4 sm:gap-6 md:gap-8") {
-                    div { a(class="hover:underline", href="/about") { "about" } }
-                    div { a(class="hover:underline", href="https://github.com/lukechu10") { "github" } }
+                div(class="self-end flex flex-row items-center gap-4 sm:gap-6 md:gap-8") {
+                    // Searching is done on the search page, which reads the query from the URL.
+                    form(class="hidden sm:block", action="/search", method="get", role="search") {
+                        input(
+                            r#type="search",
+                            name="q",
+                            class="w-32 px-1 rounded bg-slate-900 border border-slate-700 text-slate-200",
+                            placeholder="search",
+                            aria-label="Search posts",
+                        )
+                    }
+                    div(class="sm:hidden") { a(class="hover:underline", href="/search") { "search" } }
+                    (nav)
                 }
             }
         }
@@ -118,10 +388,11 @@ fn Header() -> View {
 
 #[component]
 fn Footer() -> View {
+    let copyright = format!("© {} {}", site().copyright_year, site().author);
     view! {
         footer(class="p-2 bg-slate-900 text-xs font-mono") {
             div(class="flex flex-row justify-between") {
-                div { "© 2026 Luke Chu" }
+                div { (copyright) }
                 div(class="text-[9pt]") {
                     "Made with "
                     a(class="hover:underline font-bold text-red-200", href="https://rust-lang.org") { "Rust" }
@@ -142,133 +413,3 @@ pub fn NotFound() -> View {
         }
     }
 }
-
-/// Trunk autoreload script. We need to inject this manually into our HTML file since we are
-/// generating the HTML file by hand.
-static AUTORELOAD: &str = r##""use strict";
-(function () {
-    const address = 'localhost:8000/';
-    const base = '';
-    let protocol = '';
-    protocol =
-        protocol
-            ? protocol
-            : window.location.protocol === 'https:'
-                ? 'wss'
-                : 'ws';
-    const url = protocol + '://' + address + base + '.well-known/trunk/ws';
-
-    class Overlay {
-        constructor() {
-            // create an overlay
-            this._overlay = document.createElement("div");
-            const style = this._overlay.style;
-            style.height = "100vh";
-            style.width = "100vw";
-            style.position = "fixed";
-            style.top = "0";
-            style.left = "0";
-            style.backgroundColor = "rgba(222, 222, 222, 0.5)";
-            style.fontFamily = "sans-serif";
-            // not sure that's the right approach
-            style.zIndex = "1000000";
-            style.backdropFilter = "blur(1rem)";
-
-            const container = document.createElement("div");
-            // center it
-            container.style.position = "absolute";
-            container.style.top = "30%";
-            container.style.left = "15%";
-            container.style.maxWidth = "85%";
-
-            this._title = document.createElement("div");
-            this._title.innerText = "Build failure";
-            this._title.style.paddingBottom = "2rem";
-            this._title.style.fontSize = "2.5rem";
-
-            this._message = document.createElement("div");
-            this._message.style.whiteSpace = "pre-wrap";
-
-            const icon= document.createElement("div");
-            icon.innerHTML = '<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64" fill="#dc3545" viewBox="0 0 16 16"><path d="M8.982 1.566a1.13 1.13 0 0 0-1.96 0L.165 13.233c-.457.778.091 1.767.98 1.767h13.713c.889 0 1.438-.99.98-1.767L8.982 1.566zM8 5c.535 0 .954.462.9.995l-.35 3.507a.552.552 0 0 1-1.1 0L7.1 5.995A.905.905 0 0 1 8 5zm.002 6a1 1 0 1 1 0 2 1 1 0 0 1 0-2z"/></svg>';
-            this._title.prepend(icon);
-
-            container.append(this._title, this._message);
-            this._overlay.append(container);
-
-            this._inject();
-            window.setInterval(() => {
-                this._inject();
-            }, 250);
-        }
-
-        set reason(reason) {
-            this._message.textContent = reason;
-        }
-
-        _inject() {
-            if (!this._overlay.isConnected) {
-                // prepend it
-                document.body?.prepend(this._overlay);
-            }
-        }
-
-    }
-
-    class Client {
-        constructor(url) {
-            this.url = url;
-            this.poll_interval = 5000;
-            this._overlay = null;
-        }
-
-        start() {
-            const ws = new WebSocket(this.url);
-            ws.onmessage = (ev) => {
-                const msg = JSON.parse(ev.data);
-                switch (msg.type) {
-                    case "reload":
-                        this.reload();
-                        break;
-                    case "buildFailure":
-                        this.buildFailure(msg.data)
-                        break;
-                }
-            };
-            ws.onclose = () => this.onclose();
-        }
-
-        onclose() {
-            window.setTimeout(
-                () => {
-                    // when we successfully reconnect, we'll force a
-                    // reload (since we presumably lost connection to
-                    // trunk due to it being killed, so it will have
-                    // rebuilt on restart)
-                    const ws = new WebSocket(this.url);
-                    ws.onopen = () => window.location.reload();
-                    ws.onclose = () => this.onclose();
-                },
-                this.poll_interval);
-        }
-
-        reload() {
-            window.location.reload();
-        }
-
-        buildFailure({reason}) {
-            // also log the console
-            console.error("Build failed:", reason);
-
-            console.debug("Overlay", this._overlay);
-
-            if (!this._overlay) {
-                this._overlay = new Overlay();
-            }
-            this._overlay.reason = reason;
-        }
-    }
-
-    new Client(url).start();
-
-})()"##;